@@ -7,6 +7,8 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+mod weights;
+
 use pallet_grandpa::{
     fg_primitives,
     AuthorityId,
@@ -23,6 +25,7 @@ use sp_runtime::{
     generic,
     impl_opaque_keys,
     traits::{
+        AccountIdConversion,
         BlakeTwo256,
         Block as BlockT,
         IdentifyAccount,
@@ -32,6 +35,7 @@ use sp_runtime::{
     },
     transaction_validity::TransactionValidity,
     ApplyExtrinsicResult,
+    ModuleId,
     MultiSignature,
 };
 use sp_std::prelude::*;
@@ -44,7 +48,10 @@ pub use frame_support::{
     debug,
     parameter_types,
     traits::{
+        Currency,
+        Filter,
         KeyOwnerProofSystem,
+        OnUnbalanced,
         Randomness,
     },
     weights::{
@@ -301,20 +308,75 @@ impl pallet_transaction_payment::Trait for Runtime {
     type WeightToFee = IdentityFee<Balance>;
     type FeeMultiplierUpdate = ();
 }
+parameter_types! {
+    pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+}
+
+/// Pot that receives bonds slashed by the delegate module
+pub struct Treasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Runtime>> for Treasury {
+    fn on_nonzero_unbalanced(
+        amount: pallet_balances::NegativeImbalance<Runtime>,
+    ) {
+        Balances::resolve_creating(
+            &TreasuryModuleId::get().into_account(),
+            amount,
+        );
+    }
+}
+
 parameter_types! {
     pub const Bond: Balance = 10;
     pub const MaxSize: u32 = 5;
     pub const MaxDepth: u32 = 5;
     pub const MaxKids: u32 = 2;
+    pub const MaxExpiries: u32 = 16;
+    pub const DelegateModuleId: ModuleId = ModuleId(*b"py/deleg");
+    pub const ProposalLifetime: BlockNumber = 7 * DAYS;
+    pub const MaxProposals: u32 = 8;
+    pub const InviteLifetime: BlockNumber = 7 * DAYS;
+    pub const MaxNameLength: u32 = 32;
+    pub const MaxUriLength: u32 = 128;
+    pub const MetadataDepositPerByte: Balance = 1;
+    pub const MaxRootsPerAccount: u32 = 8;
+    pub const MaxBondPerAccount: Balance = 1_000_000_000;
+    /// Drain deferred revocations with up to 10% of the block weight.
+    pub RevocationBudget: Weight = Perbill::from_percent(10) * MaximumBlockWeight::get();
+}
+/// Calls that delegation trees may dispatch from their sovereign accounts
+pub struct TreeCallFilter;
+impl Filter<Call> for TreeCallFilter {
+    fn filter(call: &Call) -> bool {
+        matches!(
+            call,
+            Call::Balances(_) | Call::System(frame_system::Call::remark(..))
+        )
+    }
 }
 impl delegate::Trait for Runtime {
     type Event = Event;
     type TreeId = TreeId;
     type Bond = Bond;
+    type BondPolicy = delegate::ExponentialBond;
     type MaxSize = MaxSize;
     type MaxDepth = MaxDepth;
     type MaxKids = MaxKids;
+    type MaxExpiries = MaxExpiries;
+    type RevocationBudget = RevocationBudget;
     type Currency = Balances;
+    type Slash = Treasury;
+    type ModuleId = DelegateModuleId;
+    type Call = Call;
+    type CallFilter = TreeCallFilter;
+    type ProposalLifetime = ProposalLifetime;
+    type MaxProposals = MaxProposals;
+    type InviteLifetime = InviteLifetime;
+    type MaxNameLength = MaxNameLength;
+    type MaxUriLength = MaxUriLength;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type MaxRootsPerAccount = MaxRootsPerAccount;
+    type MaxBondPerAccount = MaxBondPerAccount;
+    type WeightInfo = weights::delegate::WeightInfo;
 }
 
 construct_runtime!(
@@ -331,7 +393,7 @@ construct_runtime!(
         Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
         TransactionPayment: pallet_transaction_payment::{Module, Storage},
         // delegate module
-        Delegate: delegate::{Module, Call, Storage, Event<T>},
+        Delegate: delegate::{Module, Call, Storage, Event<T>, Config<T>},
     }
 );
 
@@ -476,4 +538,63 @@ impl_runtime_apis! {
             None
         }
     }
+
+    impl delegate_runtime_api::DelegateApi<Block, TreeId, AccountId, BlockNumber> for Runtime {
+        fn tree(id: TreeId) -> Option<delegate::TreeState<TreeId, AccountId, BlockNumber>> {
+            Delegate::trees(id)
+        }
+
+        fn members(id: TreeId) -> Vec<AccountId> {
+            Delegate::member_list(id)
+        }
+
+        fn children(id: TreeId) -> Vec<TreeId> {
+            Delegate::kid_list(id)
+        }
+
+        fn ancestors(id: TreeId) -> Vec<TreeId> {
+            Delegate::ancestors(id)
+        }
+
+        fn is_member(id: TreeId, account: AccountId) -> bool {
+            Delegate::is_member(id, &account)
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    impl frame_benchmarking::Benchmark<Block> for Runtime {
+        fn dispatch_benchmark(
+            pallet: Vec<u8>,
+            benchmark: Vec<u8>,
+            lowest_range_values: Vec<u32>,
+            highest_range_values: Vec<u32>,
+            steps: Vec<u32>,
+            repeat: u32,
+        ) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+            use frame_benchmarking::{
+                add_benchmark,
+                BenchmarkBatch,
+                Benchmarking,
+            };
+
+            let whitelist: Vec<Vec<u8>> = vec![];
+            let mut batches = Vec::<BenchmarkBatch>::new();
+            let params = (
+                &pallet,
+                &benchmark,
+                &lowest_range_values,
+                &highest_range_values,
+                &steps,
+                repeat,
+                &whitelist,
+            );
+
+            add_benchmark!(params, batches, b"delegate", Delegate);
+
+            if batches.is_empty() {
+                return Err("Benchmark not found for this pallet.".into())
+            }
+            Ok(batches)
+        }
+    }
 }
@@ -0,0 +1,191 @@
+//! Weights for delegate
+//! -> hand-written estimates, not benchmark output, with storage accesses
+//! counted from the code; `revoke` counts every tree and every member
+//! removed. Replace them with the output of a node built with
+//! `--features runtime-benchmarks`:
+//! `test-node benchmark --chain dev --execution wasm --wasm-execution
+//! compiled --pallet delegate --extrinsic '*' --steps 50 --repeat 20`
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{
+    constants::RocksDbWeight as DbWeight,
+    Weight,
+};
+
+pub struct WeightInfo;
+impl delegate::WeightInfo for WeightInfo {
+    fn create_root() -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+    }
+    fn delegate(m: u32) -> Weight {
+        (78_000_000 as Weight)
+            .saturating_add((6_100_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((1 as Weight).saturating_mul(m as Weight)),
+            )
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .writes((1 as Weight).saturating_mul(m as Weight)),
+            )
+    }
+    fn revoke(t: u32, m: u32) -> Weight {
+        (0 as Weight)
+            .saturating_add((72_000_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add((28_500_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((8 as Weight).saturating_mul(t as Weight)),
+            )
+            .saturating_add(
+                DbWeight::get()
+                    .reads((3 as Weight).saturating_mul(m as Weight)),
+            )
+            .saturating_add(
+                DbWeight::get()
+                    .writes((16 as Weight).saturating_mul(t as Weight)),
+            )
+            .saturating_add(
+                DbWeight::get()
+                    .writes((3 as Weight).saturating_mul(m as Weight)),
+            )
+    }
+    fn revoke_deferred() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn add_members(m: u32) -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add((6_300_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((1 as Weight).saturating_mul(m as Weight)),
+            )
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .writes((1 as Weight).saturating_mul(m as Weight)),
+            )
+    }
+    fn remove_members(m: u32) -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add((22_700_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((2 as Weight).saturating_mul(m as Weight)),
+            )
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .writes((2 as Weight).saturating_mul(m as Weight)),
+            )
+    }
+    fn propose_transfer() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn accept_transfer() -> Weight {
+        (74_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+    }
+    fn move_subtree(t: u32) -> Weight {
+        (96_000_000 as Weight)
+            .saturating_add((14_000_000 as Weight).saturating_mul(t as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((2 as Weight).saturating_mul(t as Weight)),
+            )
+            .saturating_add(DbWeight::get().writes(6 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .writes((1 as Weight).saturating_mul(t as Weight)),
+            )
+    }
+    fn set_manager_levels() -> Weight {
+        (26_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn dispatch_as_tree() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(4 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_threshold() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn approve_proposal(m: u32) -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add((6_900_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .reads((1 as Weight).saturating_mul(m as Weight)),
+            )
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+            .saturating_add(
+                DbWeight::get()
+                    .writes((1 as Weight).saturating_mul(m as Weight)),
+            )
+    }
+    fn cancel_proposal() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_role() -> Weight {
+        (29_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(4 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn leave() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(4 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn accept_invite() -> Weight {
+        (38_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(4 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn decline_invite() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn cancel_invite() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn set_metadata(b: u32) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((3_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn freeze() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn unfreeze() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+}
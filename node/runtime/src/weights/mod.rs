@@ -0,0 +1,3 @@
+//! Weights for the runtime's pallets
+
+pub mod delegate;
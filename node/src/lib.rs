@@ -1,23 +1,35 @@
+mod rpc;
+mod service;
+
+pub use service::{
+    new_full,
+    new_light,
+    new_partial,
+};
+
 use sc_executor::native_executor_instance;
 use sc_service::ChainType;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{
     sr25519,
     Pair,
     Public,
 };
+use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{
     IdentifyAccount,
     Verify,
 };
-use sunshine_node_utils::node_service;
 use test_runtime::{
     AccountId,
     AuraConfig,
     BalancesConfig,
+    DelegateConfig,
     GenesisConfig,
     GrandpaConfig,
     Signature,
     SystemConfig,
+    TreeId,
     WASM_BINARY,
 };
 
@@ -33,12 +45,7 @@ native_executor_instance!(
     pub Executor,
     test_runtime::api::dispatch,
     test_runtime::native_version,
-);
-
-node_service!(
-    test_runtime::opaque::Block,
-    test_runtime::RuntimeApi,
-    Executor
+    frame_benchmarking::benchmarking::HostFunctions,
 );
 
 /// Specialized `ChainSpec`.
@@ -84,6 +91,13 @@ pub fn development_config() -> ChainSpec {
                     get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
                 ],
+                // delegation trees
+                vec![(
+                    0,
+                    None,
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    vec![get_account_id_from_seed::<sr25519::Public>("Bob")],
+                )],
             )
         },
         vec![],
@@ -125,6 +139,29 @@ pub fn local_testnet_config() -> ChainSpec {
                         "Ferdie//stash",
                     ),
                 ],
+                // delegation trees
+                vec![
+                    (
+                        0,
+                        None,
+                        get_account_id_from_seed::<sr25519::Public>("Alice"),
+                        vec![
+                            get_account_id_from_seed::<sr25519::Public>("Bob"),
+                            get_account_id_from_seed::<sr25519::Public>(
+                                "Charlie",
+                            ),
+                        ],
+                    ),
+                    (
+                        1,
+                        Some(0),
+                        get_account_id_from_seed::<sr25519::Public>("Bob"),
+                        vec![
+                            get_account_id_from_seed::<sr25519::Public>("Dave"),
+                            get_account_id_from_seed::<sr25519::Public>("Eve"),
+                        ],
+                    ),
+                ],
             )
         },
         vec![],
@@ -135,9 +172,13 @@ pub fn local_testnet_config() -> ChainSpec {
     )
 }
 
+/// Delegation tree registered at genesis: (id, parent, owner, members)
+pub type GenesisTree = (TreeId, Option<TreeId>, AccountId, Vec<AccountId>);
+
 pub fn testnet_genesis(
     initial_authorities: Vec<(AuraId, GrandpaId)>,
     endowed_accounts: Vec<AccountId>,
+    trees: Vec<GenesisTree>,
 ) -> GenesisConfig {
     GenesisConfig {
         frame_system: Some(SystemConfig {
@@ -163,5 +204,6 @@ pub fn testnet_genesis(
                 .map(|x| (x.1.clone(), 1))
                 .collect(),
         }),
+        delegate: Some(DelegateConfig { trees }),
     }
 }
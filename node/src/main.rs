@@ -1,8 +1,8 @@
+use frame_benchmarking_cli::BenchmarkCmd;
 use sc_cli::{
     RunCmd,
     Runner,
     RuntimeVersion,
-    Subcommand,
     SubstrateCli,
 };
 use sc_service::{
@@ -12,6 +12,7 @@ use sc_service::{
     Role,
 };
 use structopt::StructOpt;
+use test_runtime::opaque::Block;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -22,6 +23,16 @@ pub struct Cli {
     pub run: RunCmd,
 }
 
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+    #[structopt(flatten)]
+    Base(sc_cli::Subcommand),
+
+    /// Benchmark the runtime pallets, needs `--features runtime-benchmarks`
+    #[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
+    Benchmark(BenchmarkCmd),
+}
+
 impl SubstrateCli for Cli {
     fn impl_name() -> String {
         test_node::IMPL_NAME.into()
@@ -74,7 +85,20 @@ impl SubstrateCli for Cli {
 fn main() -> sc_cli::Result<()> {
     let cli = <Cli as SubstrateCli>::from_args();
     match &cli.subcommand {
-        Some(subcommand) => {
+        Some(Subcommand::Benchmark(cmd)) => {
+            if cfg!(feature = "runtime-benchmarks") {
+                let mut runner = cli.create_runner(cmd)?;
+                force_parity_db(&mut runner);
+                runner.sync_run(|config| {
+                    cmd.run::<Block, test_node::Executor>(config)
+                })
+            } else {
+                Err("Benchmarking is not enabled, rebuild the node with \
+                     `--features runtime-benchmarks`."
+                    .into())
+            }
+        }
+        Some(Subcommand::Base(subcommand)) => {
             let mut runner = cli.create_runner(subcommand)?;
             force_parity_db(&mut runner);
             runner.run_subcommand(subcommand, |config| {
@@ -96,7 +120,6 @@ fn main() -> sc_cli::Result<()> {
                     Role::Light => test_node::new_light(config),
                     _ => test_node::new_full(config),
                 }
-                .map(|service| service.0)
             })
         }
     }
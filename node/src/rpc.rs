@@ -0,0 +1,38 @@
+//! Custom RPC extensions of the node
+use delegate_rpc::{
+    Delegate,
+    DelegateApi,
+};
+use std::sync::Arc;
+use test_runtime::{
+    opaque::Block,
+    AccountId,
+    BlockNumber,
+    TreeId,
+};
+
+/// RPC extension type of the node
+pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
+/// Full client dependencies
+pub struct FullDeps<C> {
+    /// The client instance to use
+    pub client: Arc<C>,
+}
+
+/// Instantiate all full RPC extensions
+pub fn create_full<C>(deps: FullDeps<C>) -> RpcExtension
+where
+    C: sp_api::ProvideRuntimeApi<Block>
+        + sp_blockchain::HeaderBackend<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api:
+        delegate_rpc::DelegateRuntimeApi<Block, TreeId, AccountId, BlockNumber>,
+{
+    let FullDeps { client } = deps;
+    let mut io = jsonrpc_core::IoHandler::default();
+    io.extend_with(DelegateApi::to_delegate(Delegate::new(client)));
+    io
+}
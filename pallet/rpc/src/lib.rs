@@ -0,0 +1,160 @@
+//! JSON-RPC endpoints for querying delegation trees
+pub use delegate_runtime_api::{
+    DelegateApi as DelegateRuntimeApi,
+    TreeState,
+};
+use jsonrpc_core::{
+    Error as RpcError,
+    ErrorCode,
+    Result,
+};
+use jsonrpc_derive::rpc;
+use parity_scale_codec::Codec;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+    generic::BlockId,
+    traits::Block as BlockT,
+};
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::Arc,
+};
+
+#[rpc]
+pub trait DelegateApi<BlockHash, TreeId, AccountId, BlockNumber> {
+    /// State of tree `id`, if it exists
+    #[rpc(name = "delegate_getTree")]
+    fn get_tree(
+        &self,
+        id: TreeId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<TreeState<TreeId, AccountId, BlockNumber>>>;
+
+    /// Members of tree `id`
+    #[rpc(name = "delegate_getMembers")]
+    fn get_members(
+        &self,
+        id: TreeId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<AccountId>>;
+
+    /// State of tree `id` followed by all of its descendants, breadth first
+    #[rpc(name = "delegate_getSubtree")]
+    fn get_subtree(
+        &self,
+        id: TreeId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<TreeState<TreeId, AccountId, BlockNumber>>>;
+
+    /// True iff `account` holds the permissions delegated to tree `id` as
+    /// one of its members, false while the tree is being revoked or frozen
+    #[rpc(name = "delegate_isAuthorized", alias("delegate_isMember"))]
+    fn is_authorized(
+        &self,
+        id: TreeId,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<bool>;
+}
+
+/// Error code for failed runtime API calls
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Unable to query delegation state.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+/// Implements `DelegateApi` on top of the `DelegateApi` runtime API
+pub struct Delegate<C> {
+    client: Arc<C>,
+}
+
+impl<C> Delegate<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+
+    /// Block to query, defaults to the best block
+    fn at<Block>(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block>
+    where
+        Block: BlockT,
+        C: HeaderBackend<Block>,
+    {
+        BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+    }
+}
+
+impl<C, Block, TreeId, AccountId, BlockNumber>
+    DelegateApi<<Block as BlockT>::Hash, TreeId, AccountId, BlockNumber>
+    for Delegate<C>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: DelegateRuntimeApi<Block, TreeId, AccountId, BlockNumber>,
+    TreeId: Codec + Copy,
+    AccountId: Codec,
+    BlockNumber: Codec,
+{
+    fn get_tree(
+        &self,
+        id: TreeId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<TreeState<TreeId, AccountId, BlockNumber>>> {
+        let at = self.at(at);
+        self.client
+            .runtime_api()
+            .tree(&at, id)
+            .map_err(runtime_error)
+    }
+
+    fn get_members(
+        &self,
+        id: TreeId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<AccountId>> {
+        let at = self.at(at);
+        self.client
+            .runtime_api()
+            .members(&at, id)
+            .map_err(runtime_error)
+    }
+
+    fn get_subtree(
+        &self,
+        id: TreeId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<TreeState<TreeId, AccountId, BlockNumber>>> {
+        let at = self.at(at);
+        let api = self.client.runtime_api();
+        let mut subtree = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(id);
+        // bounded by MaxKids and MaxDepth of the runtime
+        while let Some(next) = queue.pop_front() {
+            if let Some(tree) = api.tree(&at, next).map_err(runtime_error)? {
+                subtree.push(tree);
+                queue.extend(api.children(&at, next).map_err(runtime_error)?);
+            }
+        }
+        Ok(subtree)
+    }
+
+    fn is_authorized(
+        &self,
+        id: TreeId,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<bool> {
+        let at = self.at(at);
+        self.client
+            .runtime_api()
+            .is_member(&at, id, account)
+            .map_err(runtime_error)
+    }
+}
@@ -0,0 +1,26 @@
+//! Runtime API for querying delegation trees without decoding raw storage
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+pub use delegate::TreeState;
+use parity_scale_codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait DelegateApi<TreeId, AccountId, BlockNumber> where
+        TreeId: Codec,
+        AccountId: Codec,
+        BlockNumber: Codec,
+    {
+        /// State of the tree, if it exists
+        fn tree(id: TreeId) -> Option<TreeState<TreeId, AccountId, BlockNumber>>;
+        /// Members of the tree
+        fn members(id: TreeId) -> Vec<AccountId>;
+        /// Direct subtrees of the tree
+        fn children(id: TreeId) -> Vec<TreeId>;
+        /// Ancestors of the tree, from its parent up to the root
+        fn ancestors(id: TreeId) -> Vec<TreeId>;
+        /// True iff `account` is a member of the tree
+        fn is_member(id: TreeId, account: AccountId) -> bool;
+    }
+}
@@ -0,0 +1,425 @@
+//! Delegate module benchmarking
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{
+    account,
+    benchmarks,
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+
+/// Account with enough free balance to cover every bond
+fn funded<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value());
+    who
+}
+
+/// `m` distinct member accounts
+fn members<T: Trait>(m: u32) -> Vec<T::AccountId> {
+    (0..m).map(|i| account("member", i, SEED)).collect()
+}
+
+/// Register a root owned by `caller` and return its id
+fn root<T: Trait>(caller: &T::AccountId) -> T::TreeId {
+    let id = <TreeIdCounter<T>>::get();
+    Module::<T>::create_root(RawOrigin::Signed(caller.clone()).into())
+        .expect("caller is funded");
+    id
+}
+
+/// Accept the invites of `mems` to `tree`
+fn accept<T: Trait>(
+    tree: T::TreeId,
+    mems: Vec<T::AccountId>,
+) -> DispatchResult {
+    mems.into_iter().try_for_each(|m| {
+        Module::<T>::accept_invite(RawOrigin::Signed(m).into(), tree)
+    })
+}
+
+/// Invite `mems` to `tree` and accept every invite
+fn join<T: Trait>(
+    caller: &T::AccountId,
+    tree: T::TreeId,
+    mems: Vec<T::AccountId>,
+) -> DispatchResult {
+    Module::<T>::add_members(
+        RawOrigin::Signed(caller.clone()).into(),
+        tree,
+        mems.clone(),
+    )?;
+    accept::<T>(tree, mems)
+}
+
+/// Grow `root` breadth first to `t` trees of `s` members each (`caller`
+/// included), within MaxKids and MaxDepth
+fn build<T: Trait>(caller: &T::AccountId, root: T::TreeId, t: u32, s: u32) {
+    join::<T>(caller, root, members::<T>(s - 1)).expect("bounded by MaxSize");
+    let mut mems = members::<T>(s - 1);
+    mems.push(caller.clone());
+    let mut trees = vec![root];
+    let mut next = 0usize;
+    while (trees.len() as u32) < t {
+        let parent = trees[next];
+        for _ in 0..T::MaxKids::get() {
+            if trees.len() as u32 == t {
+                break
+            }
+            let id = <TreeIdCounter<T>>::get();
+            Module::<T>::delegate(
+                RawOrigin::Signed(caller.clone()).into(),
+                parent,
+                mems.clone(),
+                None,
+            )
+            .expect("bounded by MaxKids and MaxDepth");
+            accept::<T>(id, mems.clone()).expect("invited by delegate");
+            trees.push(id);
+        }
+        next += 1;
+    }
+}
+
+/// Delegate from `root` down to MaxDepth with only `caller` in each tree and
+/// return the deepest tree
+fn chain<T: Trait>(caller: &T::AccountId, root: T::TreeId) -> T::TreeId {
+    (0..T::MaxDepth::get()).fold(root, |parent, _| {
+        let id = <TreeIdCounter<T>>::get();
+        Module::<T>::delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            parent,
+            vec![caller.clone()],
+            None,
+        )
+        .expect("bounded by MaxDepth");
+        accept::<T>(id, vec![caller.clone()]).expect("invited by delegate");
+        id
+    })
+}
+
+/// Subtree with a 2 of 2 threshold and a proposal by `caller` to add `m`
+/// members, awaiting the approval of `approver`
+fn proposal<T: Trait>(
+    caller: &T::AccountId,
+    approver: &T::AccountId,
+    m: u32,
+) -> Result<T::TreeId, &'static str> {
+    let parent = root::<T>(caller);
+    let origin = || RawOrigin::Signed(caller.clone()).into();
+    join::<T>(caller, parent, vec![approver.clone()])?;
+    let id = <TreeIdCounter<T>>::get();
+    Module::<T>::delegate(origin(), parent, Vec::new(), None)?;
+    Module::<T>::set_threshold(origin(), id, Some(2))?;
+    Module::<T>::add_members(origin(), id, members::<T>(m))?;
+    Ok(id)
+}
+
+benchmarks! {
+    _ { }
+
+    create_root {
+        let caller = funded::<T>("caller", 0);
+        let id = <TreeIdCounter<T>>::get();
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        assert!(<Trees<T>>::get(id).is_some());
+    }
+
+    delegate {
+        let m in 1 .. T::MaxSize::get();
+        let caller = funded::<T>("caller", 0);
+        let parent = root::<T>(&caller);
+        let id = <TreeIdCounter<T>>::get();
+        let mems = members::<T>(m);
+    }: _(RawOrigin::Signed(caller), parent, mems, None)
+    verify {
+        assert_eq!(<PendingInvites<T>>::iter_prefix(id).count() as u32, m);
+    }
+
+    revoke {
+        let t in 1 .. Module::<T>::subtree_bound(0).0;
+        let m in 1 .. Module::<T>::subtree_bound(0).1;
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        // `m` members spread evenly over the `t` trees
+        let s = ((m + t - 1) / t).max(1).min(T::MaxSize::get());
+        build::<T>(&caller, id, t, s);
+    }: _(RawOrigin::Signed(caller), id, false)
+    verify {
+        assert!(<Trees<T>>::get(id).is_none());
+    }
+
+    revoke_deferred {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+    }: _(RawOrigin::Signed(caller), id, false)
+    verify {
+        assert!(<PendingRevocations<T>>::get(id).is_some());
+    }
+
+    add_members {
+        let m in 1 .. T::MaxSize::get() - 1;
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let mems = members::<T>(m);
+    }: _(RawOrigin::Signed(caller), id, mems)
+    verify {
+        assert_eq!(<PendingInvites<T>>::iter_prefix(id).count() as u32, m);
+    }
+
+    remove_members {
+        let m in 1 .. T::MaxSize::get() - 1;
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let mems = members::<T>(m);
+        join::<T>(&caller, id, mems.clone())?;
+    }: _(RawOrigin::Signed(caller), id, mems, false)
+    verify {
+        assert_eq!(<Trees<T>>::get(id).map(|t| t.size), Some(1));
+    }
+
+    propose_transfer {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let new_owner = funded::<T>("owner", 0);
+    }: _(RawOrigin::Signed(caller), id, new_owner.clone())
+    verify {
+        assert_eq!(<PendingTransfers<T>>::get(id), Some(new_owner));
+    }
+
+    accept_transfer {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let new_owner = funded::<T>("owner", 0);
+        Module::<T>::propose_transfer(
+            RawOrigin::Signed(caller).into(),
+            id,
+            new_owner.clone(),
+        )?;
+    }: _(RawOrigin::Signed(new_owner.clone()), id)
+    verify {
+        assert_eq!(<Trees<T>>::get(id).map(|t| t.bonded), Some(new_owner));
+    }
+
+    set_manager_levels {
+        let caller = funded::<T>("caller", 0);
+        let parent = root::<T>(&caller);
+        let id = <TreeIdCounter<T>>::get();
+        Module::<T>::delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            parent,
+            Vec::new(),
+            None,
+        )?;
+        let levels = T::MaxDepth::get();
+    }: _(RawOrigin::Signed(caller), id, levels)
+    verify {
+        assert_eq!(<ManagerLevels<T>>::get(id), levels);
+    }
+
+    dispatch_as_tree {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let call: <T as Trait>::Call =
+            frame_system::Call::<T>::remark(Vec::new()).into();
+    }: _(RawOrigin::Signed(caller), id, Box::new(call))
+    verify {
+        assert!(<Trees<T>>::get(id).is_some());
+    }
+
+    leave {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let member = funded::<T>("member", 0);
+        join::<T>(&caller, id, vec![member.clone()])?;
+    }: _(RawOrigin::Signed(member.clone()), id)
+    verify {
+        assert!(<Members<T>>::get(id, &member).is_none());
+    }
+
+    accept_invite {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let member: T::AccountId = account("member", 0, SEED);
+        Module::<T>::add_members(
+            RawOrigin::Signed(caller).into(),
+            id,
+            vec![member.clone()],
+        )?;
+    }: _(RawOrigin::Signed(member.clone()), id)
+    verify {
+        assert!(<Members<T>>::get(id, &member).is_some());
+    }
+
+    decline_invite {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let member: T::AccountId = account("member", 0, SEED);
+        Module::<T>::add_members(
+            RawOrigin::Signed(caller).into(),
+            id,
+            vec![member.clone()],
+        )?;
+    }: _(RawOrigin::Signed(member.clone()), id)
+    verify {
+        assert!(<PendingInvites<T>>::get(id, &member).is_none());
+    }
+
+    cancel_invite {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let member: T::AccountId = account("member", 0, SEED);
+        Module::<T>::add_members(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            vec![member.clone()],
+        )?;
+    }: _(RawOrigin::Signed(caller), id, member.clone())
+    verify {
+        assert!(<PendingInvites<T>>::get(id, &member).is_none());
+    }
+
+    set_metadata {
+        let b in 0 .. T::MaxNameLength::get() + T::MaxUriLength::get();
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let name = vec![0u8; b.min(T::MaxNameLength::get()) as usize];
+        let uri = vec![0u8; b.saturating_sub(T::MaxNameLength::get()) as usize];
+    }: _(RawOrigin::Signed(caller), id, name, uri, Default::default())
+    verify {
+        assert!(<TreeMetadata<T>>::get(id).is_some());
+    }
+
+    set_role {
+        let caller = funded::<T>("caller", 0);
+        let id = root::<T>(&caller);
+        let member: T::AccountId = account("member", 0, SEED);
+        join::<T>(&caller, id, vec![member.clone()])?;
+    }: _(RawOrigin::Signed(caller), id, member.clone(), Role::Member)
+    verify {
+        assert_eq!(
+            <Members<T>>::get(id, &member).map(|m| m.role),
+            Some(Role::Member)
+        );
+    }
+
+    set_threshold {
+        let caller = funded::<T>("caller", 0);
+        let parent = root::<T>(&caller);
+        let id = <TreeIdCounter<T>>::get();
+        Module::<T>::delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            parent,
+            Vec::new(),
+            None,
+        )?;
+    }: _(RawOrigin::Signed(caller), id, Some(2))
+    verify {
+        assert_eq!(<Thresholds<T>>::get(id), Some(2));
+    }
+
+    approve_proposal {
+        let m in 1 .. T::MaxSize::get();
+        let caller = funded::<T>("caller", 0);
+        let approver: T::AccountId = account("approver", 0, SEED);
+        let id = proposal::<T>(&caller, &approver, m)?;
+    }: _(RawOrigin::Signed(approver), id, 0)
+    verify {
+        assert_eq!(<PendingInvites<T>>::iter_prefix(id).count() as u32, m);
+    }
+
+    cancel_proposal {
+        let caller = funded::<T>("caller", 0);
+        let approver: T::AccountId = account("approver", 0, SEED);
+        let id = proposal::<T>(&caller, &approver, 1)?;
+    }: _(RawOrigin::Signed(caller), id, 0)
+    verify {
+        assert!(<Proposals<T>>::get(id, 0).is_none());
+    }
+
+    freeze {
+        let caller = funded::<T>("caller", 0);
+        let root = root::<T>(&caller);
+        let delegator = funded::<T>("delegator", 0);
+        join::<T>(&caller, root, vec![delegator.clone()])?;
+        // the caller only owns the root, the farthest ancestor
+        let id = chain::<T>(&delegator, root);
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(<FrozenTrees<T>>::contains_key(id));
+    }
+
+    unfreeze {
+        let caller = funded::<T>("caller", 0);
+        let root = root::<T>(&caller);
+        let delegator = funded::<T>("delegator", 0);
+        join::<T>(&caller, root, vec![delegator.clone()])?;
+        let id = chain::<T>(&delegator, root);
+        // the caller lifts a freeze by the owner of every tree below the root
+        Module::<T>::freeze(RawOrigin::Signed(delegator).into(), id)?;
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(!<FrozenTrees<T>>::contains_key(id));
+    }
+
+    move_subtree {
+        let t in 1 .. Module::<T>::subtree_bound(1).0;
+        let caller = funded::<T>("caller", 0);
+        let from = root::<T>(&caller);
+        let to = root::<T>(&caller);
+        let id = <TreeIdCounter<T>>::get();
+        Module::<T>::delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            from,
+            vec![caller.clone()],
+            None,
+        )?;
+        accept::<T>(id, vec![caller.clone()])?;
+        build::<T>(&caller, id, t, 1);
+    }: _(RawOrigin::Signed(caller), id, to)
+    verify {
+        assert_eq!(<Trees<T>>::get(id).and_then(|t| t.parent), Some(to));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        new_test_ext,
+        TestRuntime,
+    };
+    use frame_support::assert_ok;
+
+    #[test]
+    fn benchmarks_work() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create_root::<TestRuntime>());
+            assert_ok!(test_benchmark_delegate::<TestRuntime>());
+            assert_ok!(test_benchmark_revoke::<TestRuntime>());
+            assert_ok!(test_benchmark_revoke_deferred::<TestRuntime>());
+            assert_ok!(test_benchmark_add_members::<TestRuntime>());
+            assert_ok!(test_benchmark_remove_members::<TestRuntime>());
+            assert_ok!(test_benchmark_propose_transfer::<TestRuntime>());
+            assert_ok!(test_benchmark_accept_transfer::<TestRuntime>());
+            assert_ok!(test_benchmark_move_subtree::<TestRuntime>());
+            assert_ok!(test_benchmark_set_manager_levels::<TestRuntime>());
+            assert_ok!(test_benchmark_dispatch_as_tree::<TestRuntime>());
+            assert_ok!(test_benchmark_set_role::<TestRuntime>());
+            assert_ok!(test_benchmark_leave::<TestRuntime>());
+            assert_ok!(test_benchmark_accept_invite::<TestRuntime>());
+            assert_ok!(test_benchmark_decline_invite::<TestRuntime>());
+            assert_ok!(test_benchmark_cancel_invite::<TestRuntime>());
+            assert_ok!(test_benchmark_set_metadata::<TestRuntime>());
+            assert_ok!(test_benchmark_set_threshold::<TestRuntime>());
+            assert_ok!(test_benchmark_approve_proposal::<TestRuntime>());
+            assert_ok!(test_benchmark_cancel_proposal::<TestRuntime>());
+            assert_ok!(test_benchmark_freeze::<TestRuntime>());
+            assert_ok!(test_benchmark_unfreeze::<TestRuntime>());
+        });
+    }
+}
@@ -0,0 +1,111 @@
+//! Bond policies
+//! -> the amount reserved for adding members and delegating to subtrees,
+//! scaled by `Trait::Bond`
+use crate::{
+    BalanceOf,
+    Error,
+    Trait,
+};
+use frame_support::{
+    dispatch::DispatchError,
+    traits::Get,
+};
+use sp_runtime::traits::{
+    CheckedMul,
+    Saturating,
+};
+use sp_std::marker::PhantomData;
+
+/// Bond schedule for the marginal contribution of each action to worst case
+/// deletion complexity
+pub trait BondPolicy<T: Trait> {
+    /// Bond for growing a tree to `size` members
+    fn member_bond(size: u32) -> Result<BalanceOf<T>, DispatchError>;
+    /// Bond for delegating to the `kids`-th subtree at `height`
+    fn delegation_bond(
+        height: u32,
+        kids: u32,
+    ) -> Result<BalanceOf<T>, DispatchError>;
+}
+
+/// `bond * n`, failing with `BondOverflow`
+fn scale<T: Trait>(n: u32) -> Result<BalanceOf<T>, DispatchError> {
+    T::Bond::get()
+        .checked_mul(&n.into())
+        .ok_or_else(|| Error::<T>::BondOverflow.into())
+}
+
+/// Linear Bond
+/// -> `bond * size` per member, `bond * (height + kids)` per subtree
+pub struct LinearBond;
+
+impl<T: Trait> BondPolicy<T> for LinearBond {
+    fn member_bond(size: u32) -> Result<BalanceOf<T>, DispatchError> {
+        scale::<T>(size)
+    }
+    fn delegation_bond(
+        height: u32,
+        kids: u32,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let n = height.checked_add(kids).ok_or(Error::<T>::BondOverflow)?;
+        scale::<T>(n)
+    }
+}
+
+/// Exponential Bond
+/// -> `bond * size` per member, `bond^{height + kids}` per subtree
+pub struct ExponentialBond;
+
+impl<T: Trait> BondPolicy<T> for ExponentialBond {
+    fn member_bond(size: u32) -> Result<BalanceOf<T>, DispatchError> {
+        scale::<T>(size)
+    }
+    fn delegation_bond(
+        height: u32,
+        kids: u32,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let exp = height.checked_add(kids).ok_or(Error::<T>::BondOverflow)?;
+        let bond = T::Bond::get();
+        // exponent is bounded by MaxDepth + MaxKids
+        (0..exp).try_fold(1u32.into(), |acc: BalanceOf<T>, _| {
+            acc.checked_mul(&bond)
+                .ok_or_else(|| Error::<T>::BondOverflow.into())
+        })
+    }
+}
+
+/// Capped Polynomial Bond
+/// -> `bond * size^{Degree}` per member, `bond * (height + kids)^{Degree}`
+/// per subtree, each saturating at `Cap`
+pub struct CappedPolynomialBond<Degree, Cap>(PhantomData<(Degree, Cap)>);
+
+/// `bond * n^{degree}` saturating at `cap`
+fn capped<T: Trait>(n: u32, degree: u32, cap: BalanceOf<T>) -> BalanceOf<T> {
+    let n: BalanceOf<T> = n.into();
+    let mut bond = T::Bond::get();
+    for _ in 0..degree {
+        if bond >= cap {
+            break
+        }
+        bond = bond.saturating_mul(n);
+    }
+    bond.min(cap)
+}
+
+impl<T, Degree, Cap> BondPolicy<T> for CappedPolynomialBond<Degree, Cap>
+where
+    T: Trait,
+    Degree: Get<u32>,
+    Cap: Get<BalanceOf<T>>,
+{
+    fn member_bond(size: u32) -> Result<BalanceOf<T>, DispatchError> {
+        Ok(capped::<T>(size, Degree::get(), Cap::get()))
+    }
+    fn delegation_bond(
+        height: u32,
+        kids: u32,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let n = height.saturating_add(kids);
+        Ok(capped::<T>(n, Degree::get(), Cap::get()))
+    }
+}
@@ -6,7 +6,9 @@
 //! * the number of subgroups
 //! * the depth of delegation
 //! These constraints allow us to use recursion in the module
-//! with strict bounds on worst-case complexity.
+//! with strict bounds on worst-case complexity. `Module::subtree_bound`
+//! computes the bound for a subtree at any height and `revoke` is weighed
+//! against it, refunding the difference once the actual work is known.
 //!
 //! - [`delegate::Trait`](./trait.Trait.html)
 //! - [`Call`](./enum.Call.html)
@@ -20,26 +22,32 @@
 //! recursion. Each group registered on-chain has a `TreeId`. To get the state
 //! of a group, we use the `Trees` map
 //! ```rust, ignore
-//! map TreeId => Option<TreeState<T::TreeId, T::AccountId>>;
+//! map TreeId => Option<TreeState<T::TreeId, T::AccountId, T::BlockNumber>>;
 //! ```
-//! The `TreeState<_, _>` struct contains the data relevant to the bounds that
+//! Each subtree is also indexed under its parent in the `Kids` map so that
+//! recursive revocation only visits the revoked subtree
+//! ```rust, ignore
+//! double_map TreeId, TreeId => Option<()>;
+//! ```
+//! The `TreeState<_, _, _>` struct contains the data relevant to the bounds that
 //! the module places on length, width and depth.
 //! ```rust, ignore
-//! pub struct TreeState<TreeId, AccountId> {
+//! pub struct TreeState<TreeId, AccountId, BlockNumber> {
 //!     pub id: TreeId,
 //!     pub parent: Option<TreeId>,
 //!     pub bonded: AccountId,
 //!     pub height: u32,
 //!     pub kids: u32,
 //!     pub size: u32,
+//!     pub expires: Option<BlockNumber>,
 //! }
 //! ```
 //! The module's runtime configuration sets the maximum depth (`height`),
 //! number of subgroups (`kids`), and number of members (`size`). Each
-//! `TreeState<_, _>` is either a root or the child of a parent tree.
+//! `TreeState<_, _, _>` is either a root or the child of a parent tree.
 //! We define an algorithm for tree creation.
 //! ```ignore
-//! TreeCreation(parent: TreeState<_, _>)
+//! TreeCreation(parent: TreeState<_, _, _>)
 //!     let kid = TreeState {
 //!         parent: Some(parent.id)
 //!         height: parent.height + 1u32,
@@ -57,6 +65,52 @@
 //! the `Trait::MaxSize` before adding new members to the set of `AccountId`
 //! associated on-chain with the group `TreeId`.
 //!
+//! ## Roles
+//! Each member has a `Role` in the tree. Admins delegate and, as members of
+//! an ancestor, manage the membership of subtrees. Delegators only delegate
+//! and plain members only hold the permissions delegated to the tree.
+//! Members join as admins and the accounts that manage a tree change roles
+//! with `set_role`.
+//!
+//! ## Invitations
+//! `add_members` invites accounts instead of inserting them and `delegate`
+//! invites the initial members of the new tree without a bond. Invitees join
+//! with `accept_invite` or turn the invite down with `decline_invite`.
+//! Pending invites count towards `Trait::MaxSize` until they expire after
+//! `Trait::InviteLifetime` blocks, after which anyone can clear them with
+//! `cancel_invite`. Inviters' bonds are kept in `InviterBonds`, apart from
+//! memberships. The bond for each invite that is declined or cleared is
+//! returned and the rest is released with the tree.
+//!
+//! ## Metadata
+//! The owner or any member of the parent names a tree with `set_metadata`,
+//! which stores a name, a description URI and the hash of the content
+//! behind it. The caller reserves `Trait::MetadataDepositPerByte` for every
+//! byte stored, returned when the metadata is replaced or the tree revoked.
+//!
+//! ## Freezing
+//! The owner of a tree or of any of its ancestors halts activity under it
+//! with `freeze` without revoking it. While a tree or any ancestor is frozen,
+//! its members cannot delegate or change membership, the tree cannot be moved
+//! nor receive moved subtrees and the authority checks, including
+//! `EnsureTreeMember` and `EnsureTreeOwner`, fail. The account
+//! that froze a tree, or the owner of a tree above every tree it owns along
+//! the path, lifts the freeze with `unfreeze`; descendants frozen on their own
+//! stay frozen.
+//!
+//! ## Sovereign Accounts
+//! Each tree controls the account `Module::tree_account`, derived from
+//! `Trait::ModuleId` and its `TreeId`. Members dispatch calls from it with
+//! `dispatch_as_tree`, restricted to the calls allowed by `Trait::CallFilter`.
+//! Funds left in the account are not touched by revocation.
+//!
+//! ## Expiry
+//! `delegate` takes an optional `expires_at` block number. Expiring
+//! delegations are queued in `Expiries` and revoked, releasing their bonds,
+//! in `on_initialize` of that block. At most `Trait::MaxExpiries`
+//! delegations may expire in the same block so the hook stays within the
+//! worst-case revoke weight times `Trait::MaxExpiries`.
+//!
 //! ## Incentives
 //! The bounds described above are not good enough. The variance of cost for
 //! tree deletion is high because it is recursive and high variance poses a
@@ -65,9 +119,23 @@
 //! constraints and revokes the highest root. With this scenario in mind, the
 //! incentives should discourage large groups and wide or deep delegation.
 //!
+//! The schedule is set by `Trait::BondPolicy`. The module ships
+//! * `LinearBond`, linear in group size and in depth and span
+//! * `ExponentialBond`, linear in group size, exponential in depth and span
+//! * `CappedPolynomialBond`, polynomial in both, up to a cap
+//!
+//! Bonds are cheap for a single tree, so each account registers at most
+//! `Trait::MaxRootsPerAccount` roots and reserves at most
+//! `Trait::MaxBondPerAccount` across every tree. `OwnedTrees` and
+//! `AccountBonds` index both per account.
+//!
 //! ### Bonds for Adding Members Scales Linearly With Group Size
 //!
 //! ### Bonds for Delegating to Tree Scales Exponentially With Depth and Span
+//! The owner of a subtree reserves the bond for delegating it, recorded in
+//! `DelegationBonds` and released when the subtree is revoked.
+//! The owner moves the subtree with `move_subtree`, which settles the
+//! difference with the bond at the new position.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
@@ -76,19 +144,48 @@
 #[cfg(test)]
 mod tests;
 
+pub mod migration;
+
+mod bond;
+pub use bond::{
+    BondPolicy,
+    CappedPolynomialBond,
+    ExponentialBond,
+    LinearBond,
+};
+
+mod origin;
+pub use origin::{
+    EnsureTreeMember,
+    EnsureTreeOwner,
+};
+
+mod set;
+pub use set::BoundedSet;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 use frame_support::{
     decl_error,
     decl_event,
     decl_module,
     decl_storage,
-    dispatch::DispatchError,
+    dispatch::DispatchResultWithPostInfo,
     ensure,
-    storage::IterableStorageMap,
     traits::{
         Currency,
+        Filter,
         Get,
+        Imbalance,
+        OnUnbalanced,
         ReservableCurrency,
     },
+    weights::{
+        GetDispatchInfo,
+        PostDispatchInfo,
+        Weight,
+    },
     Parameter,
 };
 use frame_system::{
@@ -100,14 +197,24 @@ use parity_scale_codec::{
     Decode,
     Encode,
 };
+#[cfg(feature = "std")]
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use sp_runtime::{
     traits::{
+        AccountIdConversion,
         AtLeast32Bit,
+        CheckedAdd,
+        Dispatchable,
         MaybeSerializeDeserialize,
         Member,
+        Saturating,
         Zero,
     },
     DispatchResult,
+    ModuleId,
 };
 use sp_std::{
     fmt::Debug,
@@ -115,18 +222,227 @@ use sp_std::{
 };
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
-pub struct TreeState<TreeId, AccountId> {
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TreeState<TreeId, AccountId, BlockNumber> {
     pub id: TreeId,
     pub parent: Option<TreeId>,
     pub bonded: AccountId,
     pub height: u32,
     pub kids: u32,
     pub size: u32,
+    pub expires: Option<BlockNumber>,
+}
+
+/// Permissions of a member within a tree
+#[derive(
+    PartialEq, Eq, Clone, Copy, Encode, Decode, sp_runtime::RuntimeDebug,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Role {
+    /// Delegates and manages the membership of subtrees
+    Admin,
+    /// Delegates
+    Delegator,
+    /// Holds the permissions delegated to the tree
+    Member,
+}
+
+impl Role {
+    pub fn can_delegate(self) -> bool {
+        self != Role::Member
+    }
+    pub fn can_manage(self) -> bool {
+        self == Role::Admin
+    }
+}
+
+impl Default for Role {
+    /// Members join with the permissions every member had before roles
+    fn default() -> Self {
+        Role::Admin
+    }
+}
+
+/// Value of `Members`
+#[derive(
+    PartialEq, Eq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug,
+)]
+pub struct Membership<Balance> {
+    /// Bond reserved by the member for actions on the tree
+    pub bond: Balance,
+    pub role: Role,
+}
+
+/// Membership change awaiting approval
+#[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum MembershipChange<AccountId> {
+    Add(Vec<AccountId>),
+    /// Members, penalty
+    Remove(Vec<AccountId>, bool),
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct Proposal<AccountId, BlockNumber> {
+    pub change: MembershipChange<AccountId>,
+    pub proposer: AccountId,
+    pub approvals: Vec<AccountId>,
+    pub expires: BlockNumber,
+}
+
+/// Invitation to join a tree, accepted or declined by the invitee
+#[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct Invite<AccountId, Balance, BlockNumber> {
+    pub inviter: AccountId,
+    /// Share of the inviter's bond returned if the invite is not accepted
+    pub bond: Balance,
+    pub expires: BlockNumber,
+}
+
+/// Description of a tree, paid for by a per-byte deposit
+#[derive(PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct Metadata<AccountId, Balance, Hash> {
+    pub name: Vec<u8>,
+    pub uri: Vec<u8>,
+    /// Hash of the content behind `uri`
+    pub hash: Hash,
+    /// Account that reserved the deposit
+    pub depositor: AccountId,
+    pub deposit: Balance,
+}
+
+/// Storage layout versions, see `migration`
+#[derive(
+    PartialEq, Eq, Clone, Copy, Encode, Decode, sp_runtime::RuntimeDebug,
+)]
+pub enum Releases {
+    /// `TreeState` without `expires`
+    V0,
+    /// `TreeState` with `expires`
+    V1,
+    /// `Members` with `Role`
+    V2,
+    /// `OwnedTrees` and `AccountBonds` indexes
+    V3,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V0
+    }
 }
 
 type BalanceOf<T> =
     <<T as Trait>::Currency as Currency<<T as System>::AccountId>>::Balance;
-type TreeSt<T> = TreeState<<T as Trait>::TreeId, <T as System>::AccountId>;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<
+    <T as System>::AccountId,
+>>::NegativeImbalance;
+type TreeSt<T> = TreeState<
+    <T as Trait>::TreeId,
+    <T as System>::AccountId,
+    <T as System>::BlockNumber,
+>;
+type InviteOf<T> =
+    Invite<<T as System>::AccountId, BalanceOf<T>, <T as System>::BlockNumber>;
+type MetadataOf<T> =
+    Metadata<<T as System>::AccountId, BalanceOf<T>, <T as System>::Hash>;
+type MemberSet<T> = BoundedSet<<T as System>::AccountId, <T as Trait>::MaxSize>;
+
+/// Weight functions for the module's dispatchables, generated by the
+/// benchmarks in `benchmarking.rs`
+pub trait WeightInfo {
+    fn create_root() -> Weight;
+    fn delegate(m: u32) -> Weight;
+    fn revoke(t: u32, m: u32) -> Weight;
+    fn revoke_deferred() -> Weight;
+    fn add_members(m: u32) -> Weight;
+    fn remove_members(m: u32) -> Weight;
+    fn propose_transfer() -> Weight;
+    fn accept_transfer() -> Weight;
+    fn move_subtree(t: u32) -> Weight;
+    fn set_manager_levels() -> Weight;
+    fn dispatch_as_tree() -> Weight;
+    fn set_threshold() -> Weight;
+    fn approve_proposal(m: u32) -> Weight;
+    fn cancel_proposal() -> Weight;
+    fn set_role() -> Weight;
+    fn leave() -> Weight;
+    fn accept_invite() -> Weight;
+    fn decline_invite() -> Weight;
+    fn cancel_invite() -> Weight;
+    fn set_metadata(b: u32) -> Weight;
+    fn freeze() -> Weight;
+    fn unfreeze() -> Weight;
+}
+
+impl WeightInfo for () {
+    fn create_root() -> Weight {
+        1_000_000_000
+    }
+    fn delegate(_m: u32) -> Weight {
+        1_000_000_000
+    }
+    fn revoke(_t: u32, _m: u32) -> Weight {
+        1_000_000_000
+    }
+    fn revoke_deferred() -> Weight {
+        1_000_000_000
+    }
+    fn add_members(_m: u32) -> Weight {
+        1_000_000_000
+    }
+    fn remove_members(_m: u32) -> Weight {
+        1_000_000_000
+    }
+    fn propose_transfer() -> Weight {
+        1_000_000_000
+    }
+    fn accept_transfer() -> Weight {
+        1_000_000_000
+    }
+    fn move_subtree(_t: u32) -> Weight {
+        1_000_000_000
+    }
+    fn set_manager_levels() -> Weight {
+        1_000_000_000
+    }
+    fn dispatch_as_tree() -> Weight {
+        1_000_000_000
+    }
+    fn set_threshold() -> Weight {
+        1_000_000_000
+    }
+    fn approve_proposal(_m: u32) -> Weight {
+        1_000_000_000
+    }
+    fn cancel_proposal() -> Weight {
+        1_000_000_000
+    }
+    fn set_role() -> Weight {
+        1_000_000_000
+    }
+    fn leave() -> Weight {
+        1_000_000_000
+    }
+    fn accept_invite() -> Weight {
+        1_000_000_000
+    }
+    fn decline_invite() -> Weight {
+        1_000_000_000
+    }
+    fn cancel_invite() -> Weight {
+        1_000_000_000
+    }
+    fn set_metadata(_b: u32) -> Weight {
+        1_000_000_000
+    }
+    fn freeze() -> Weight {
+        1_000_000_000
+    }
+    fn unfreeze() -> Weight {
+        1_000_000_000
+    }
+}
+
 pub trait Trait: System {
     /// Overarching event type
     type Event: From<Event<Self>> + Into<<Self as System>::Event>;
@@ -144,9 +460,12 @@ pub trait Trait: System {
         + PartialEq
         + Zero;
 
-    /// Bond amount, charged per depth
+    /// Bond amount, the unit scaled by `BondPolicy`
     type Bond: Get<BalanceOf<Self>>;
 
+    /// Bond schedule for adding members and delegating to subtrees
+    type BondPolicy: BondPolicy<Self>;
+
     /// Maximum group size for all trees
     type MaxSize: Get<u32>;
 
@@ -156,9 +475,59 @@ pub trait Trait: System {
     /// Maximum number of subtrees per tree
     type MaxKids: Get<u32>;
 
+    /// Maximum number of delegations expiring in the same block
+    type MaxExpiries: Get<u32>;
+
+    /// Weight budget per block for draining `PendingRevocations`
+    type RevocationBudget: Get<Weight>;
+
     /// Currency type
     type Currency: Currency<Self::AccountId>
         + ReservableCurrency<Self::AccountId>;
+
+    /// Destination for bonds slashed upon removal with penalty
+    type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+    /// Prefix of the sovereign account of each tree
+    type ModuleId: Get<ModuleId>;
+
+    /// Calls dispatched from the sovereign account of a tree
+    type Call: Parameter
+        + Dispatchable<
+            Origin = <Self as System>::Origin,
+            PostInfo = PostDispatchInfo,
+        > + GetDispatchInfo
+        + From<frame_system::Call<Self>>;
+
+    /// Calls that members may dispatch from the sovereign account of a tree
+    type CallFilter: Filter<<Self as Trait>::Call>;
+
+    /// Number of blocks a membership proposal can be approved for
+    type ProposalLifetime: Get<Self::BlockNumber>;
+
+    /// Maximum number of open membership proposals per tree
+    type MaxProposals: Get<u32>;
+
+    /// Number of blocks an invitation can be accepted for
+    type InviteLifetime: Get<Self::BlockNumber>;
+
+    /// Maximum length in bytes of a tree's name
+    type MaxNameLength: Get<u32>;
+
+    /// Maximum length in bytes of a tree's description URI
+    type MaxUriLength: Get<u32>;
+
+    /// Deposit reserved per byte of tree metadata
+    type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+    /// Maximum number of roots registered by one account
+    type MaxRootsPerAccount: Get<u32>;
+
+    /// Maximum total bond reserved by one account across all trees
+    type MaxBondPerAccount: Get<BalanceOf<Self>>;
+
+    /// Weight information for the module's dispatchables
+    type WeightInfo: WeightInfo;
 }
 
 decl_event!(
@@ -169,10 +538,54 @@ decl_event!(
         Balance = BalanceOf<T>,
     {
         RegisterIdRoot(TreeId, AccountId, Balance),
-        AddedMembers(AccountId, TreeId, Balance),
-        RemovedMembers(AccountId, TreeId),
-        DelegateBranch(TreeId, TreeId, AccountId, Balance),
-        RevokeDelegation(TreeId),
+        /// Inviter, tree, invited accounts, bond reserved for the invites
+        MembersInvited(AccountId, TreeId, Vec<AccountId>, Balance),
+        InviteAccepted(TreeId, AccountId),
+        /// Tree, invitee, bond returned to the inviter
+        InviteDeclined(TreeId, AccountId, Balance),
+        /// Tree, invitee, bond returned to the inviter
+        InviteCancelled(TreeId, AccountId, Balance),
+        /// Tree, caller, deposit reserved
+        MetadataSet(TreeId, AccountId, Balance),
+        /// Caller, tree, removed members, bond released
+        RemovedMembers(AccountId, TreeId, Vec<AccountId>, Balance),
+        /// Parent, new tree, owner, invited members, bond reserved from the owner
+        DelegateBranch(TreeId, TreeId, AccountId, Vec<AccountId>, Balance),
+        /// Revoked tree, trees removed, members removed, bond released
+        RevokeDelegation(TreeId, u32, u32, Balance),
+        /// Expired tree, trees removed, members removed, bond released
+        DelegationExpired(TreeId, u32, u32, Balance),
+        /// Tree removed with its members, bond released
+        SubtreeRemoved(TreeId, u32, Balance),
+        RevocationScheduled(TreeId),
+        /// Tree removed under root TreeId, u32 trees still pending
+        RevocationProgress(TreeId, TreeId, u32),
+        RevocationCompleted(TreeId),
+        TransferProposed(TreeId, AccountId, AccountId),
+        /// Tree, previous owner, new owner, bond moved
+        OwnershipTransferred(TreeId, AccountId, AccountId, Balance),
+        /// Tree, old parent, new parent, caller, owner's bond at the new position
+        SubtreeMoved(TreeId, TreeId, TreeId, AccountId, Balance),
+        /// Tree, number of ancestor levels whose members manage its membership
+        ManagerLevelsSet(TreeId, u32),
+        /// Tree, dispatching member, result of the call
+        TreeDispatched(TreeId, AccountId, DispatchResult),
+        /// Tree, number of approvals required for membership changes
+        ThresholdSet(TreeId, Option<u32>),
+        RoleSet(TreeId, AccountId, Role),
+        /// Tree, member, bond released
+        Left(TreeId, AccountId, Balance),
+        /// Tree, owner of the tree or of an ancestor
+        TreeFrozen(TreeId, AccountId),
+        /// Tree, owner of the tree or of an ancestor
+        TreeUnfrozen(TreeId, AccountId),
+        /// Tree, proposal index, proposer
+        ProposalCreated(TreeId, u32, AccountId),
+        /// Tree, proposal index, approver, approvals so far
+        ProposalApproved(TreeId, u32, AccountId, u32),
+        ProposalExecuted(TreeId, u32),
+        ProposalCancelled(TreeId, u32),
+        Slashed(TreeId, AccountId, Balance),
     }
 );
 
@@ -184,6 +597,49 @@ decl_error! {
         CannotAddGroupAboveMaxSize,
         CannotDelegateBelowMaxDepth,
         CannotDelegateAboveMaxKids,
+        ExpiryNotInFuture,
+        TooManyExpiriesAtBlock,
+        TreeRevoking,
+        NoPendingTransfer,
+        // Bond does not fit in the balance type
+        BondOverflow,
+        CannotMoveRoot,
+        AlreadyKidOfParent,
+        // New parent is the tree or one of its descendants
+        MoveCreatesCycle,
+        // Manager levels must be between 1 and MaxDepth
+        InvalidManagerLevels,
+        // Call may not be dispatched from a tree's sovereign account
+        CallFiltered,
+        // Thresholds must be between 1 and MaxSize
+        InvalidThreshold,
+        // Root membership is managed by the owner alone
+        ThresholdOnRoot,
+        ProposalDNE,
+        AlreadyApproved,
+        ProposalExpired,
+        // The tree already has MaxProposals open proposals
+        TooManyProposals,
+        // Delegations start with at most MaxSize members
+        TooManyInitialMembers,
+        // No tree has more than MaxSize members to remove
+        TooManyMembersToRemove,
+        NotMember,
+        // The owner keeps the permissions to revoke and delegate
+        CannotSetOwnerRole,
+        // The owner transfers ownership before leaving
+        OwnerCannotLeave,
+        InviteDNE,
+        InviteExpired,
+        NameTooLong,
+        UriTooLong,
+        // The account already owns MaxRootsPerAccount roots
+        TooManyRoots,
+        // The account's total bond would exceed MaxBondPerAccount
+        BondAboveMaxPerAccount,
+        // The tree or one of its ancestors is frozen
+        TreeFrozen,
+        NotFrozen,
     }
 }
 
@@ -196,10 +652,99 @@ decl_storage! {
         pub Trees get(fn trees): map
             hasher(blake2_128_concat) T::TreeId => Option<TreeSt<T>>;
 
-        /// Membership, also tracks bonded amount for existing members
+        /// Child index, parent => child for every delegated subtree
+        pub Kids get(fn kids): double_map
+            hasher(blake2_128_concat) T::TreeId,
+            hasher(blake2_128_concat) T::TreeId => Option<()>;
+
+        /// Membership, also tracks bonded amount and role for existing members
         pub Members get(fn members): double_map
             hasher(blake2_128_concat) T::TreeId,
-            hasher(blake2_128_concat) T::AccountId => Option<BalanceOf<T>>;
+            hasher(blake2_128_concat) T::AccountId => Option<Membership<BalanceOf<T>>>;
+
+        /// Bond reserved from the owner of each subtree for delegating it
+        pub DelegationBonds get(fn delegation_bond): map
+            hasher(blake2_128_concat) T::TreeId => BalanceOf<T>;
+
+        /// Delegations revoked at the start of the block, bounded by MaxExpiries
+        pub Expiries get(fn expiries): map
+            hasher(twox_64_concat) T::BlockNumber => Vec<T::TreeId>;
+
+        /// Trees marked for deferred revocation => (revoked root, penalty)
+        pub PendingRevocations get(fn pending_revocations): map
+            hasher(blake2_128_concat) T::TreeId => Option<(T::TreeId, bool)>;
+
+        /// Number of trees still pending per deferred revocation root
+        pub PendingCount get(fn pending_count): map
+            hasher(blake2_128_concat) T::TreeId => u32;
+
+        /// Proposed owner of a tree, pending acceptance
+        pub PendingTransfers get(fn pending_transfers): map
+            hasher(blake2_128_concat) T::TreeId => Option<T::AccountId>;
+
+        /// Number of ancestor levels whose members manage a tree's membership
+        pub ManagerLevels get(fn manager_levels): map
+            hasher(blake2_128_concat) T::TreeId => u32 = 1;
+
+        /// Approvals required for membership changes, none for immediate changes
+        pub Thresholds get(fn threshold): map
+            hasher(blake2_128_concat) T::TreeId => Option<u32>;
+
+        /// Membership changes awaiting approval, by tree and proposal index
+        pub Proposals get(fn proposals): double_map
+            hasher(blake2_128_concat) T::TreeId,
+            hasher(twox_64_concat) u32 => Option<Proposal<T::AccountId, T::BlockNumber>>;
+
+        /// Next proposal index per tree
+        pub ProposalCount get(fn proposal_count): map
+            hasher(blake2_128_concat) T::TreeId => u32;
+
+        /// Number of open proposals per tree, bounded by MaxProposals
+        pub OpenProposals get(fn open_proposals): map
+            hasher(blake2_128_concat) T::TreeId => u32;
+
+        /// Invitations awaiting the invitee's answer, counted towards MaxSize
+        /// until they expire
+        pub PendingInvites get(fn pending_invites): double_map
+            hasher(blake2_128_concat) T::TreeId,
+            hasher(blake2_128_concat) T::AccountId => Option<InviteOf<T>>;
+
+        /// Bond reserved by each inviter for the invites to a tree
+        pub InviterBonds get(fn inviter_bond): double_map
+            hasher(blake2_128_concat) T::TreeId,
+            hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+        /// Name, description URI and content hash of trees
+        pub TreeMetadata get(fn metadata): map
+            hasher(blake2_128_concat) T::TreeId => Option<MetadataOf<T>>;
+
+        /// Roots registered by each account, at most MaxRootsPerAccount
+        pub OwnedTrees get(fn owned_trees): map
+            hasher(blake2_128_concat) T::AccountId => Vec<T::TreeId>;
+
+        /// Total bond reserved by each account, excluding metadata deposits
+        pub AccountBonds get(fn account_bond): map
+            hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+        /// Frozen trees and the account that froze them, the freeze covers
+        /// their descendants
+        pub FrozenTrees get(fn frozen): map
+            hasher(blake2_128_concat) T::TreeId => Option<T::AccountId>;
+
+        /// Storage layout version, new chains start at the latest
+        StorageVersion build(|_| Releases::V3): Releases;
+    }
+    add_extra_genesis {
+        /// Trees as (id, parent, owner, members), parents before their kids
+        config(trees): Vec<(T::TreeId, Option<T::TreeId>, T::AccountId, Vec<T::AccountId>)>;
+        build(|config: &GenesisConfig<T>| {
+            config.trees.iter().for_each(|(id, parent, owner, members)| {
+                Module::<T>::genesis_tree(*id, *parent, owner, members);
+            });
+            if let Some(last) = config.trees.iter().map(|t| t.0).max() {
+                <TreeIdCounter<T>>::put(last + 1u32.into());
+            }
+        });
     }
 }
 
@@ -208,13 +753,26 @@ decl_module! {
         type Error = Error<T>;
         fn deposit_event() = default;
 
-        #[weight = 0]
+        fn on_runtime_upgrade() -> Weight {
+            migration::migrate::<T>()
+        }
+
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            Self::expire(n).saturating_add(Self::drain_revocations())
+        }
+
+        #[weight = T::WeightInfo::create_root()]
         fn create_root(
             origin,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            let bond = T::Bond::get();
-            T::Currency::reserve(&caller, bond)?;
+            ensure!(
+                (Self::owned_trees(&caller).len() as u32)
+                    < T::MaxRootsPerAccount::get(),
+                Error::<T>::TooManyRoots
+            );
+            let bond = T::BondPolicy::member_bond(1u32)?;
+            Self::reserve(&caller, bond)?;
             let id = Self::gen_uid();
             let state = TreeState {
                 id,
@@ -223,28 +781,42 @@ decl_module! {
                 height: 0u32,
                 kids: 0u32,
                 size: 1u32,
+                expires: None,
             };
             <Trees<T>>::insert(id, state);
-            <Members<T>>::insert(id, caller.clone(), bond);
+            <Members<T>>::insert(id, caller.clone(), Membership { bond, role: Role::Admin });
+            <OwnedTrees<T>>::append(&caller, id);
             Self::deposit_event(RawEvent::RegisterIdRoot(id, caller, bond));
             Ok(())
         }
-        #[weight = 0]
+        #[weight = T::WeightInfo::delegate(members.len() as u32)]
         fn delegate(
             origin,
             parent: T::TreeId,
             members: Vec<T::AccountId>,
+            expires_at: Option<T::BlockNumber>,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
-            ensure!(<Members<T>>::get(parent, &caller).is_some(), Error::<T>::NotAuthorized);
+            ensure!(!Self::is_frozen(parent), Error::<T>::TreeFrozen);
+            ensure!(Self::can_delegate(parent, &caller), Error::<T>::NotAuthorized);
             let parent_st = <Trees<T>>::get(parent).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(Self::is_active(parent), Error::<T>::TreeRevoking);
             let (new_kids, new_height) = (parent_st.kids + 1u32, parent_st.height + 1u32);
             // check that delegating does not violate module kids constraints (num of children)
             ensure!(new_kids <= T::MaxKids::get(), Error::<T>::CannotDelegateAboveMaxKids);
             // check that delegating does not violate module depth constraints
             ensure!(new_height <= T::MaxDepth::get(), Error::<T>::CannotDelegateBelowMaxDepth);
-            let bond = Self::reserve_exponential_bond(parent, &caller, new_height, new_kids)?;
+            let members = MemberSet::<T>::try_from_vec(members).ok_or(Error::<T>::TooManyInitialMembers)?;
+            // check that expiry is in the future and the expiry block has capacity
+            if let Some(at) = expires_at {
+                ensure!(at > <frame_system::Module<T>>::block_number(), Error::<T>::ExpiryNotInFuture);
+                let scheduled = <Expiries<T>>::get(at).len() as u32;
+                ensure!(scheduled < T::MaxExpiries::get(), Error::<T>::TooManyExpiriesAtBlock);
+            }
+            let bond = T::BondPolicy::delegation_bond(new_height, new_kids)?;
+            Self::reserve(&caller, bond)?;
             let id = Self::gen_uid();
+            <DelegationBonds<T>>::insert(id, bond);
             let state = TreeState {
                 id,
                 parent: Some(parent_st.id),
@@ -252,26 +824,52 @@ decl_module! {
                 height: new_height,
                 kids: 0u32,
                 size: 0u32,
+                expires: expires_at,
             };
-            Self::add_mems(state, members);
+            <Trees<T>>::insert(id, state);
+            // initial members join like any other invitee
+            let invited = members.into_vec();
+            Self::invite(id, &caller, invited.clone(), Zero::zero());
+            <Kids<T>>::insert(parent, id, ());
+            if let Some(at) = expires_at {
+                <Expiries<T>>::append(at, id);
+            }
             <Trees<T>>::insert(parent, TreeState {kids: new_kids, ..parent_st});
-            Self::deposit_event(RawEvent::DelegateBranch(parent, id, caller, bond));
+            Self::deposit_event(RawEvent::DelegateBranch(parent, id, caller, invited, bond));
             Ok(())
         }
-        #[weight = 0]
+        #[weight = Module::<T>::revoke_weight(*branch)]
         fn revoke(
             origin,
             branch: T::TreeId,
             penalty: bool,
+        ) -> DispatchResultWithPostInfo {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(branch).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(tree.bonded == caller, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(branch), Error::<T>::TreeRevoking);
+            let (trees, mems, released) = Self::remove_mems(tree, None, penalty);
+            Self::deposit_event(RawEvent::RevokeDelegation(branch, trees, mems, released));
+            // refund the difference between the worst case and the actual work
+            Ok(Some(Self::removal_weight(trees, mems)).into())
+        }
+        #[weight = T::WeightInfo::revoke_deferred()]
+        fn revoke_deferred(
+            origin,
+            branch: T::TreeId,
+            penalty: bool,
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
             let tree = <Trees<T>>::get(branch).ok_or(Error::<T>::TreeDNE)?;
             ensure!(tree.bonded == caller, Error::<T>::NotAuthorized);
-            Self::remove_mems(tree, None, penalty);
-            Self::deposit_event(RawEvent::RevokeDelegation(branch));
+            ensure!(Self::is_active(branch), Error::<T>::TreeRevoking);
+            // the mark blocks authorization for the whole subtree until drained
+            <PendingRevocations<T>>::insert(branch, (branch, penalty));
+            <PendingCount<T>>::insert(branch, 1u32);
+            Self::deposit_event(RawEvent::RevocationScheduled(branch));
             Ok(())
         }
-        #[weight = 0]
+        #[weight = T::WeightInfo::add_members(members.len() as u32)]
         fn add_members(
             origin,
             tree_id: T::TreeId,
@@ -279,20 +877,16 @@ decl_module! {
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
             let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
-            // auth requires member of direct parent || bonded caller
-            let auth = if let Some(p) = tree.parent {
-                <Members<T>>::get(p, &caller).is_some()
-            } else { tree.bonded == caller };
-            ensure!(auth, Error::<T>::NotAuthorized);
-            let mut mems = members; mems.dedup();
-            let new_size = mems.len() as u32 + tree.size;
-            ensure!(new_size <= T::MaxSize::get(), Error::<T>::CannotAddGroupAboveMaxSize);
-            let bond = Self::reserve_linear_bond(tree_id, &caller, new_size)?;
-            Self::add_mems(tree, mems);
-            Self::deposit_event(RawEvent::AddedMembers(caller, tree_id, bond));
-            Ok(())
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!Self::is_frozen(tree_id), Error::<T>::TreeFrozen);
+            ensure!(Self::can_manage(&tree, &caller), Error::<T>::NotAuthorized);
+            let members = MemberSet::<T>::try_from_vec(members).ok_or(Error::<T>::CannotAddGroupAboveMaxSize)?;
+            if Self::needs_approval(tree_id) {
+                return Self::propose(tree_id, caller, MembershipChange::Add(members.into_vec()))
+            }
+            Self::do_add_members(caller, tree, members)
         }
-        #[weight = 0]
+        #[weight = T::WeightInfo::remove_members(members.len() as u32)]
         fn remove_members(
             origin,
             tree_id: T::TreeId,
@@ -301,15 +895,350 @@ decl_module! {
         ) -> DispatchResult {
             let caller = ensure_signed(origin)?;
             let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
-            // auth requires member of direct parent || bonded caller
-            let auth = if let Some(p) = tree.parent {
-                <Members<T>>::get(p, &caller).is_some()
-            } else { tree.bonded == caller };
-            ensure!(auth, Error::<T>::NotAuthorized);
-            Self::remove_mems(tree, Some(members), penalty);
-            Self::deposit_event(RawEvent::RemovedMembers(caller, tree_id));
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!Self::is_frozen(tree_id), Error::<T>::TreeFrozen);
+            ensure!(Self::can_manage(&tree, &caller), Error::<T>::NotAuthorized);
+            let members = MemberSet::<T>::try_from_vec(members).ok_or(Error::<T>::TooManyMembersToRemove)?;
+            if Self::needs_approval(tree_id) {
+                return Self::propose(tree_id, caller, MembershipChange::Remove(members.into_vec(), penalty))
+            }
+            Self::do_remove_members(caller, tree, members, penalty);
+            Ok(())
+        }
+        #[weight = T::WeightInfo::leave()]
+        fn leave(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let membership = <Members<T>>::get(tree_id, &caller).ok_or(Error::<T>::NotMember)?;
+            ensure!(tree.bonded != caller, Error::<T>::OwnerCannotLeave);
+            // a pending revocation with penalty slashes the bond
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            Self::remove_mems(tree, Some(vec![caller.clone()]), false);
+            Self::deposit_event(RawEvent::Left(tree_id, caller, membership.bond));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::set_role()]
+        fn set_role(
+            origin,
+            tree_id: T::TreeId,
+            account: T::AccountId,
+            role: Role,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!Self::is_frozen(tree_id), Error::<T>::TreeFrozen);
+            ensure!(Self::can_manage(&tree, &caller), Error::<T>::NotAuthorized);
+            ensure!(tree.bonded != account, Error::<T>::CannotSetOwnerRole);
+            let mut membership = <Members<T>>::get(tree_id, &account).ok_or(Error::<T>::NotMember)?;
+            membership.role = role;
+            <Members<T>>::insert(tree_id, &account, membership);
+            Self::deposit_event(RawEvent::RoleSet(tree_id, account, role));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::set_threshold()]
+        fn set_threshold(
+            origin,
+            tree_id: T::TreeId,
+            threshold: Option<u32>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(tree.bonded == caller, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(tree.parent.is_some(), Error::<T>::ThresholdOnRoot);
+            if let Some(t) = threshold {
+                ensure!(t >= 1 && t <= T::MaxSize::get(), Error::<T>::InvalidThreshold);
+                <Thresholds<T>>::insert(tree_id, t);
+            } else {
+                <Thresholds<T>>::remove(tree_id);
+            }
+            Self::deposit_event(RawEvent::ThresholdSet(tree_id, threshold));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::approve_proposal(T::MaxSize::get())]
+        fn approve_proposal(
+            origin,
+            tree_id: T::TreeId,
+            index: u32,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let mut proposal = <Proposals<T>>::get(tree_id, index).ok_or(Error::<T>::ProposalDNE)?;
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!Self::is_frozen(tree_id), Error::<T>::TreeFrozen);
+            ensure!(Self::can_manage(&tree, &caller), Error::<T>::NotAuthorized);
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(now < proposal.expires, Error::<T>::ProposalExpired);
+            ensure!(!proposal.approvals.contains(&caller), Error::<T>::AlreadyApproved);
+            proposal.approvals.push(caller.clone());
+            let approvals = proposal.approvals.len() as u32;
+            if approvals < Self::threshold(tree_id).unwrap_or(1) {
+                <Proposals<T>>::insert(tree_id, index, proposal);
+                Self::deposit_event(RawEvent::ProposalApproved(tree_id, index, caller, approvals));
+                return Ok(())
+            }
+            // executed on behalf of the proposer, who bears any bond
+            match proposal.change {
+                MembershipChange::Add(mems) => {
+                    let mems = MemberSet::<T>::try_from_vec(mems).ok_or(Error::<T>::CannotAddGroupAboveMaxSize)?;
+                    Self::do_add_members(proposal.proposer, tree, mems)?
+                }
+                MembershipChange::Remove(mems, penalty) => {
+                    let mems = MemberSet::<T>::try_from_vec(mems).ok_or(Error::<T>::TooManyMembersToRemove)?;
+                    Self::do_remove_members(proposal.proposer, tree, mems, penalty)
+                }
+            }
+            Self::close_proposal(tree_id, index);
+            Self::deposit_event(RawEvent::ProposalExecuted(tree_id, index));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::cancel_proposal()]
+        fn cancel_proposal(
+            origin,
+            tree_id: T::TreeId,
+            index: u32,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let proposal = <Proposals<T>>::get(tree_id, index).ok_or(Error::<T>::ProposalDNE)?;
+            // anyone can clean up an expired proposal
+            let expired = <frame_system::Module<T>>::block_number() >= proposal.expires;
+            ensure!(proposal.proposer == caller || expired, Error::<T>::NotAuthorized);
+            Self::close_proposal(tree_id, index);
+            Self::deposit_event(RawEvent::ProposalCancelled(tree_id, index));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::accept_invite()]
+        fn accept_invite(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let invite = <PendingInvites<T>>::get(tree_id, &caller).ok_or(Error::<T>::InviteDNE)?;
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!Self::is_frozen(tree_id), Error::<T>::TreeFrozen);
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(now < invite.expires, Error::<T>::InviteExpired);
+            <PendingInvites<T>>::remove(tree_id, &caller);
+            Self::add_mems(tree, vec![caller.clone()]);
+            Self::deposit_event(RawEvent::InviteAccepted(tree_id, caller));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::decline_invite()]
+        fn decline_invite(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let invite = <PendingInvites<T>>::get(tree_id, &caller).ok_or(Error::<T>::InviteDNE)?;
+            // a pending revocation with penalty slashes the inviter's bond
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            <PendingInvites<T>>::remove(tree_id, &caller);
+            let refund = Self::refund_invite(tree_id, &invite);
+            Self::deposit_event(RawEvent::InviteDeclined(tree_id, caller, refund));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::cancel_invite()]
+        fn cancel_invite(
+            origin,
+            tree_id: T::TreeId,
+            invitee: T::AccountId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let invite = <PendingInvites<T>>::get(tree_id, &invitee).ok_or(Error::<T>::InviteDNE)?;
+            // anyone can clean up an expired invite
+            let expired = <frame_system::Module<T>>::block_number() >= invite.expires;
+            ensure!(invite.inviter == caller || expired, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            <PendingInvites<T>>::remove(tree_id, &invitee);
+            let refund = Self::refund_invite(tree_id, &invite);
+            Self::deposit_event(RawEvent::InviteCancelled(tree_id, invitee, refund));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::propose_transfer()]
+        fn propose_transfer(
+            origin,
+            tree_id: T::TreeId,
+            new_owner: T::AccountId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(tree.bonded == caller, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            // overwrites any previous proposal
+            <PendingTransfers<T>>::insert(tree_id, new_owner.clone());
+            Self::deposit_event(RawEvent::TransferProposed(tree_id, caller, new_owner));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::accept_transfer()]
+        fn accept_transfer(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let proposed = <PendingTransfers<T>>::get(tree_id).ok_or(Error::<T>::NoPendingTransfer)?;
+            ensure!(proposed == caller, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            Self::transfer_bond(tree, caller)?;
+            <PendingTransfers<T>>::remove(tree_id);
+            Ok(())
+        }
+        #[weight = T::WeightInfo::set_manager_levels()]
+        fn set_manager_levels(
+            origin,
+            tree_id: T::TreeId,
+            levels: u32,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(tree.bonded == caller, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(levels >= 1 && levels <= T::MaxDepth::get(), Error::<T>::InvalidManagerLevels);
+            <ManagerLevels<T>>::insert(tree_id, levels);
+            Self::deposit_event(RawEvent::ManagerLevelsSet(tree_id, levels));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::set_metadata((name.len() + uri.len()) as u32)]
+        fn set_metadata(
+            origin,
+            tree_id: T::TreeId,
+            name: Vec<u8>,
+            uri: Vec<u8>,
+            hash: T::Hash,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let parent_member = tree.parent.map_or(false, |p| Self::is_member(p, &caller));
+            ensure!(tree.bonded == caller || parent_member, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(name.len() as u32 <= T::MaxNameLength::get(), Error::<T>::NameTooLong);
+            ensure!(uri.len() as u32 <= T::MaxUriLength::get(), Error::<T>::UriTooLong);
+            let bytes = (name.len() + uri.len() + hash.as_ref().len()) as u32;
+            let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+            T::Currency::reserve(&caller, deposit)?;
+            // the deposit for replaced metadata goes back to whoever paid it
+            if let Some(old) = <TreeMetadata<T>>::get(tree_id) {
+                T::Currency::unreserve(&old.depositor, old.deposit);
+            }
+            <TreeMetadata<T>>::insert(tree_id, Metadata {
+                name,
+                uri,
+                hash,
+                depositor: caller.clone(),
+                deposit,
+            });
+            Self::deposit_event(RawEvent::MetadataSet(tree_id, caller, deposit));
+            Ok(())
+        }
+        #[weight = T::WeightInfo::freeze()]
+        fn freeze(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(Self::can_freeze(&tree, &caller), Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id), Error::<T>::TreeRevoking);
+            ensure!(!<FrozenTrees<T>>::contains_key(tree_id), Error::<T>::TreeFrozen);
+            <FrozenTrees<T>>::insert(tree_id, caller.clone());
+            Self::deposit_event(RawEvent::TreeFrozen(tree_id, caller));
             Ok(())
         }
+        #[weight = T::WeightInfo::unfreeze()]
+        fn unfreeze(
+            origin,
+            tree_id: T::TreeId,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            ensure!(Self::can_freeze(&tree, &caller), Error::<T>::NotAuthorized);
+            let freezer = <FrozenTrees<T>>::get(tree_id)
+                .ok_or(Error::<T>::NotFrozen)?;
+            ensure!(
+                Self::can_unfreeze(&tree, &freezer, &caller),
+                Error::<T>::NotAuthorized
+            );
+            <FrozenTrees<T>>::remove(tree_id);
+            Self::deposit_event(RawEvent::TreeUnfrozen(tree_id, caller));
+            Ok(())
+        }
+        #[weight = {
+            let info = call.get_dispatch_info();
+            (T::WeightInfo::dispatch_as_tree().saturating_add(info.weight), info.class)
+        }]
+        fn dispatch_as_tree(
+            origin,
+            tree_id: T::TreeId,
+            call: Box<<T as Trait>::Call>,
+        ) -> DispatchResult {
+            let caller = ensure_signed(origin)?;
+            ensure!(<Trees<T>>::contains_key(tree_id), Error::<T>::TreeDNE);
+            ensure!(Self::is_member(tree_id, &caller), Error::<T>::NotAuthorized);
+            ensure!(T::CallFilter::filter(&call), Error::<T>::CallFiltered);
+            let origin = frame_system::RawOrigin::Signed(Self::tree_account(tree_id));
+            let result = call.dispatch(origin.into());
+            Self::deposit_event(RawEvent::TreeDispatched(
+                tree_id,
+                caller,
+                result.map(|_| ()).map_err(|e| e.error),
+            ));
+            Ok(())
+        }
+        #[weight = Module::<T>::move_weight(*tree_id)]
+        fn move_subtree(
+            origin,
+            tree_id: T::TreeId,
+            new_parent: T::TreeId,
+        ) -> DispatchResultWithPostInfo {
+            let caller = ensure_signed(origin)?;
+            let tree = <Trees<T>>::get(tree_id).ok_or(Error::<T>::TreeDNE)?;
+            let old_parent = tree.parent.ok_or(Error::<T>::CannotMoveRoot)?;
+            ensure!(old_parent != new_parent, Error::<T>::AlreadyKidOfParent);
+            let parent_st = <Trees<T>>::get(new_parent).ok_or(Error::<T>::TreeDNE)?;
+            // the new parent cannot be the tree or one of its descendants
+            let cycle = new_parent == tree_id || Self::ancestors(new_parent).contains(&tree_id);
+            ensure!(!cycle, Error::<T>::MoveCreatesCycle);
+            // a frozen tree stays under the trees whose owners can unfreeze it
+            ensure!(
+                !Self::is_frozen(tree_id) && !Self::is_frozen(new_parent),
+                Error::<T>::TreeFrozen
+            );
+            // auth requires the owner, who bears the bond, to be a member of
+            // both the old and the new parent
+            let auth = tree.bonded == caller
+                && Self::can_delegate(old_parent, &caller)
+                && Self::can_delegate(new_parent, &caller);
+            ensure!(auth, Error::<T>::NotAuthorized);
+            ensure!(Self::is_active(tree_id) && Self::is_active(new_parent), Error::<T>::TreeRevoking);
+            let (new_kids, new_height) = (parent_st.kids + 1u32, parent_st.height + 1u32);
+            ensure!(new_kids <= T::MaxKids::get(), Error::<T>::CannotDelegateAboveMaxKids);
+            // the deepest descendant moves by the same number of levels
+            let subtree = Self::subtree_list(tree_id);
+            let deepest = subtree.iter().map(|t| t.height).max().unwrap_or(tree.height);
+            ensure!(
+                deepest - tree.height + new_height <= T::MaxDepth::get(),
+                Error::<T>::CannotDelegateBelowMaxDepth
+            );
+            let bond = T::BondPolicy::delegation_bond(new_height, new_kids)?;
+            // the owner's bond is settled against the bond at the new position
+            let old_bond = <DelegationBonds<T>>::get(tree_id);
+            if bond > old_bond {
+                Self::reserve(&tree.bonded, bond - old_bond)?;
+            } else if bond < old_bond {
+                Self::unreserve(&tree.bonded, old_bond - bond);
+            }
+            <DelegationBonds<T>>::insert(tree_id, bond);
+            let moved = subtree.len() as u32;
+            Self::relink(subtree, new_parent, new_height);
+            <Trees<T>>::insert(new_parent, TreeState {kids: new_kids, ..parent_st});
+            Self::deposit_event(RawEvent::SubtreeMoved(tree_id, old_parent, new_parent, caller, bond));
+            Ok(Some(T::WeightInfo::move_subtree(moved)).into())
+        }
     }
 }
 
@@ -322,87 +1251,756 @@ impl<T: Trait> Module<T> {
         while <Trees<T>>::get(counter).is_some() {
             counter += 1u32.into();
         }
+        // revoked ids are never reused
+        <TreeIdCounter<T>>::put(counter + 1u32.into());
         counter
     }
-    /// Linear Bond
-    /// -> bond amount scales linearly with number of members in Tree
-    pub fn reserve_linear_bond(
+    /// Sovereign Account of Tree
+    /// -> derived from `Trait::ModuleId` and the id, outlives the tree
+    pub fn tree_account(tree: T::TreeId) -> T::AccountId {
+        T::ModuleId::get().into_sub_account(tree)
+    }
+    /// Members of Tree
+    pub fn member_list(tree: T::TreeId) -> Vec<T::AccountId> {
+        <Members<T>>::iter_prefix(tree).map(|(a, _)| a).collect()
+    }
+    /// Direct Subtrees of Tree
+    pub fn kid_list(tree: T::TreeId) -> Vec<T::TreeId> {
+        <Kids<T>>::iter_prefix(tree).map(|(k, _)| k).collect()
+    }
+    /// Ancestors of Tree
+    /// -> ordered from the direct parent up to the root, bounded by MaxDepth
+    pub fn ancestors(tree: T::TreeId) -> Vec<T::TreeId> {
+        let mut ancestors = Vec::new();
+        let mut next = <Trees<T>>::get(tree).and_then(|t| t.parent);
+        while let Some(p) = next {
+            ancestors.push(p);
+            next = <Trees<T>>::get(p).and_then(|t| t.parent);
+        }
+        ancestors
+    }
+    /// Subtree of Tree
+    /// -> the tree followed by all of its descendants, breadth first,
+    /// bounded by `subtree_bound`
+    pub fn subtree_list(tree: T::TreeId) -> Vec<TreeSt<T>> {
+        let mut subtree = Vec::new();
+        let mut next = 0usize;
+        if let Some(t) = <Trees<T>>::get(tree) {
+            subtree.push(t);
+        }
+        while next < subtree.len() {
+            let kids = Self::kid_list(subtree[next].id)
+                .into_iter()
+                .filter_map(|k| <Trees<T>>::get(k))
+                .collect::<Vec<_>>();
+            subtree.extend(kids);
+            next += 1;
+        }
+        subtree
+    }
+    /// Membership Check
+    /// -> false while the tree is being revoked or frozen
+    pub fn is_member(tree: T::TreeId, account: &T::AccountId) -> bool {
+        <Members<T>>::get(tree, account).is_some()
+            && Self::is_active(tree)
+            && !Self::is_frozen(tree)
+    }
+    /// Authority Check
+    /// -> true iff the account is a member of one of the first `max_levels`
+    /// ancestors of the tree and the tree is active and not frozen, bounded
+    /// by MaxDepth
+    pub fn is_authorized(
         tree: T::TreeId,
         account: &T::AccountId,
-        new_size: u32,
-    ) -> Result<BalanceOf<T>, DispatchError> {
-        let bond: BalanceOf<T> = T::Bond::get() * new_size.into();
-        T::Currency::reserve(account, bond)?;
-        let b = if let Some(total) = <Members<T>>::get(tree, account) {
-            total + bond
+        max_levels: u32,
+    ) -> bool {
+        Self::ancestors(tree)
+            .into_iter()
+            .take(max_levels as usize)
+            .any(|p| {
+                <Members<T>>::get(p, account)
+                    .map_or(false, |m| m.role.can_manage())
+            })
+            && Self::is_active(tree)
+            && !Self::is_frozen(tree)
+    }
+    /// Management Check
+    /// -> members of an ancestor within ManagerLevels manage subtrees, the
+    /// bonded account manages roots unless they are frozen
+    pub fn can_manage(tree: &TreeSt<T>, account: &T::AccountId) -> bool {
+        if tree.parent.is_some() {
+            Self::is_authorized(tree.id, account, Self::manager_levels(tree.id))
         } else {
-            bond
-        };
-        <Members<T>>::insert(tree, account, b);
-        Ok(bond)
+            tree.bonded == *account && !Self::is_frozen(tree.id)
+        }
     }
-    /// Exponential Bond
-    /// -> bond amount scales exponentially with height and number of kids
-    /// ```(bond)^{height} * (bond)^{kids} = (bond)^{height + kids}```
-    pub fn reserve_exponential_bond(
-        tree: T::TreeId,
+    /// Delegation Check
+    /// -> true iff the account is a member of the tree with a role that
+    /// delegates and the tree is not frozen
+    pub fn can_delegate(tree: T::TreeId, account: &T::AccountId) -> bool {
+        <Members<T>>::get(tree, account)
+            .map_or(false, |m| m.role.can_delegate())
+            && !Self::is_frozen(tree)
+    }
+    /// Freezing Check
+    /// -> true iff the account owns the tree or one of its ancestors,
+    /// bounded by MaxDepth
+    pub fn can_freeze(tree: &TreeSt<T>, account: &T::AccountId) -> bool {
+        tree.bonded == *account
+            || Self::ancestors(tree.id).into_iter().any(|p| {
+                <Trees<T>>::get(p).map_or(false, |t| t.bonded == *account)
+            })
+    }
+    /// Unfreezing Check
+    /// -> true iff the account froze the tree or owns a tree above every
+    /// tree the freezer owns along the path to the root, bounded by MaxDepth
+    pub fn can_unfreeze(
+        tree: &TreeSt<T>,
+        freezer: &T::AccountId,
         account: &T::AccountId,
-        height: u32,
-        kids: u32,
-    ) -> Result<BalanceOf<T>, DispatchError> {
-        let exp = (height + kids) as usize;
-        // Exponential closure n ^ exp
-        // - no punishment for calling this and not having enough balance is an attack vector
-        // -- could match on reservation error and deduct a fee but would cause storage noop
-        let power = |n: BalanceOf<T>, exp: usize| {
-            vec![n; exp]
-                .iter()
-                .fold(BalanceOf::<T>::zero() + 1u32.into(), |a, b| a * *b)
-        };
-        let bond: BalanceOf<T> = power(T::Bond::get(), exp);
-        T::Currency::reserve(account, bond)?;
-        let b = if let Some(total) = <Members<T>>::get(tree, account) {
-            total + bond
+    ) -> bool {
+        if freezer == account {
+            return true
+        }
+        // owners from the tree up to the root
+        let owners = sp_std::iter::once(tree.bonded.clone())
+            .chain(
+                Self::ancestors(tree.id)
+                    .into_iter()
+                    .filter_map(|p| <Trees<T>>::get(p).map(|t| t.bonded)),
+            )
+            .collect::<Vec<_>>();
+        let above = owners
+            .iter()
+            .rposition(|o| o == freezer)
+            .map_or(0, |i| i + 1);
+        owners[above..].contains(account)
+    }
+    /// Approval Check
+    /// -> true iff membership changes need more than one approval
+    pub fn needs_approval(tree: T::TreeId) -> bool {
+        Self::threshold(tree).map_or(false, |t| t > 1)
+    }
+    /// Activity Check
+    /// -> false iff the tree or any ancestor is marked for deferred
+    /// revocation, bounded by MaxDepth
+    pub fn is_active(tree: T::TreeId) -> bool {
+        let mut next = Some(tree);
+        while let Some(t) = next {
+            if <PendingRevocations<T>>::contains_key(t) {
+                return false
+            }
+            next = <Trees<T>>::get(t).and_then(|s| s.parent);
+        }
+        true
+    }
+    /// Frozen Check
+    /// -> true iff the tree or any ancestor is frozen, bounded by MaxDepth
+    pub fn is_frozen(tree: T::TreeId) -> bool {
+        let mut next = Some(tree);
+        while let Some(t) = next {
+            if <FrozenTrees<T>>::contains_key(t) {
+                return true
+            }
+            next = <Trees<T>>::get(t).and_then(|s| s.parent);
+        }
+        false
+    }
+    /// Worst Case Subtree
+    /// -> maximum number of (trees, members) under and including a tree at
+    /// `height`, i.e. a full `MaxKids`-ary tree down to `MaxDepth` with
+    /// `MaxSize` members per tree
+    /// ```sum_{i = 0}^{MaxDepth - height} MaxKids^{i}```
+    pub fn subtree_bound(height: u32) -> (u32, u32) {
+        let levels = T::MaxDepth::get().saturating_sub(height);
+        let (mut trees, mut width) = (1u32, 1u32);
+        for _ in 0..levels {
+            width = width.saturating_mul(T::MaxKids::get());
+            trees = trees.saturating_add(width);
+        }
+        (trees, trees.saturating_mul(T::MaxSize::get()))
+    }
+    /// Revoke Weight
+    /// -> worst case weight for revoking `branch`, bounded by its height
+    pub fn revoke_weight(branch: T::TreeId) -> Weight {
+        let height = <Trees<T>>::get(branch).map(|t| t.height).unwrap_or(0);
+        let (trees, mems) = Self::subtree_bound(height);
+        Self::removal_weight(trees, mems)
+    }
+    /// Move Weight
+    /// -> worst case weight for moving `tree`, bounded by its height
+    pub fn move_weight(tree: T::TreeId) -> Weight {
+        let height = <Trees<T>>::get(tree).map(|t| t.height).unwrap_or(0);
+        T::WeightInfo::move_subtree(Self::subtree_bound(height).0)
+    }
+    /// Removal Weight
+    /// -> actual weight of removing `trees` trees with `mems` members in total
+    pub fn removal_weight(trees: u32, mems: u32) -> Weight {
+        T::WeightInfo::revoke(trees, mems)
+            .saturating_add(Self::proposals_weight(trees))
+    }
+    /// Proposals Weight
+    /// -> worst case weight for removing the open proposals of `trees`
+    /// trees, bounded by MaxProposals per tree
+    pub fn proposals_weight(trees: u32) -> Weight {
+        let open = trees.saturating_mul(T::MaxProposals::get());
+        T::DbWeight::get().writes(open.into())
+    }
+    /// Expire Delegations
+    /// -> revokes every delegation expiring at `n`, bounded by
+    /// MaxExpiries * worst case revoke weight
+    fn expire(n: T::BlockNumber) -> Weight {
+        <Expiries<T>>::take(n).into_iter().fold(
+            T::DbWeight::get().reads_writes(1, 1),
+            |w, id| {
+                if let Some(tree) = <Trees<T>>::get(id) {
+                    let (trees, mems, released) =
+                        Self::remove_mems(tree, None, false);
+                    Self::deposit_event(RawEvent::DelegationExpired(
+                        id, trees, mems, released,
+                    ));
+                    w.saturating_add(Self::removal_weight(trees, mems))
+                } else {
+                    // already revoked
+                    w.saturating_add(T::DbWeight::get().reads(1))
+                }
+            },
+        )
+    }
+    /// Drain Deferred Revocations
+    /// -> removes one tree at a time, queueing its kids under the same root,
+    /// until the next tree could exceed RevocationBudget
+    fn drain_revocations() -> Weight {
+        let max_kids: Weight = T::MaxKids::get().into();
+        let step = Self::removal_weight(1, T::MaxSize::get()).saturating_add(
+            T::DbWeight::get().reads_writes(max_kids, max_kids),
+        );
+        let budget = T::RevocationBudget::get();
+        let mut used = T::DbWeight::get().reads(1);
+        while used.saturating_add(step) <= budget {
+            let (id, (root, penalty)) =
+                if let Some(next) = <PendingRevocations<T>>::iter().next() {
+                    next
+                } else {
+                    break
+                };
+            used = used.saturating_add(step);
+            <PendingRevocations<T>>::remove(id);
+            let mut pending = <PendingCount<T>>::get(root).saturating_sub(1);
+            // already removed trees have no kids left to queue
+            if let Some(tree) = <Trees<T>>::get(id) {
+                let kids = Self::kid_list(id);
+                Self::remove_tree(tree, penalty);
+                // kids queued under their own root drain with that root
+                kids.into_iter()
+                    .filter(|k| !<PendingRevocations<T>>::contains_key(k))
+                    .for_each(|k| {
+                        pending += 1;
+                        <PendingRevocations<T>>::insert(k, (root, penalty));
+                    });
+            }
+            Self::deposit_event(RawEvent::RevocationProgress(
+                root, id, pending,
+            ));
+            if pending == 0 {
+                <PendingCount<T>>::remove(root);
+                Self::deposit_event(RawEvent::RevocationCompleted(root));
+            } else {
+                <PendingCount<T>>::insert(root, pending);
+            }
+        }
+        used
+    }
+    /// Genesis Tree
+    /// -> registers a root or delegates a subtree as `owner`, reserving the
+    /// same bonds as `create_root` with `add_members` or `delegate`; panics
+    /// if the tree breaks the module constraints
+    fn genesis_tree(
+        id: T::TreeId,
+        parent: Option<T::TreeId>,
+        owner: &T::AccountId,
+        members: &[T::AccountId],
+    ) {
+        assert!(!<Trees<T>>::contains_key(id), "genesis tree ids are unique");
+        let mut mems = MemberSet::<T>::try_from_vec(members.to_vec())
+            .expect("genesis size above MaxSize");
+        let (height, size) = if let Some(p) = parent {
+            let parent_st = <Trees<T>>::get(p)
+                .expect("genesis parents are listed before their kids");
+            assert!(
+                <Members<T>>::contains_key(p, owner),
+                "genesis owners are members of the parent"
+            );
+            let (kids, height) = (parent_st.kids + 1, parent_st.height + 1);
+            assert!(kids <= T::MaxKids::get(), "genesis kids above MaxKids");
+            assert!(
+                height <= T::MaxDepth::get(),
+                "genesis height above MaxDepth"
+            );
+            let bond = T::BondPolicy::delegation_bond(height, kids)
+                .expect("genesis bonds fit in the balance type");
+            Self::reserve(owner, bond)
+                .expect("genesis owners can afford their bonds");
+            <DelegationBonds<T>>::insert(id, bond);
+            <Kids<T>>::insert(p, id, ());
+            <Trees<T>>::insert(p, TreeState { kids, ..parent_st });
+            (height, 0u32)
         } else {
-            bond
+            let bond = T::BondPolicy::member_bond(1u32)
+                .expect("genesis bonds fit in the balance type");
+            assert!(
+                (Self::owned_trees(owner).len() as u32)
+                    < T::MaxRootsPerAccount::get(),
+                "genesis roots above MaxRootsPerAccount"
+            );
+            Self::reserve(owner, bond)
+                .expect("genesis owners can afford their bonds");
+            <Members<T>>::insert(
+                id,
+                owner,
+                Membership {
+                    bond,
+                    role: Role::Admin,
+                },
+            );
+            <OwnedTrees<T>>::append(owner, id);
+            mems.retain(|m| m != owner);
+            (0u32, 1u32)
+        };
+        let new_size = size + mems.len();
+        assert!(new_size <= T::MaxSize::get(), "genesis size above MaxSize");
+        if parent.is_none() && new_size > size {
+            let bond = T::BondPolicy::member_bond(new_size)
+                .expect("genesis bonds fit in the balance type");
+            Self::reserve_inviter_bond(id, owner, bond)
+                .expect("genesis owners can afford their bonds");
+        }
+        let state = TreeState {
+            id,
+            parent,
+            bonded: owner.clone(),
+            height,
+            kids: 0u32,
+            size,
+            expires: None,
         };
-        <Members<T>>::insert(tree, account, b);
-        Ok(bond)
+        Self::add_mems(state, mems.into_vec());
+    }
+    /// Propose Membership Change
+    /// -> stores the change with the proposer's approval until
+    /// ProposalLifetime passes, bounded by MaxProposals open per tree
+    fn propose(
+        tree: T::TreeId,
+        proposer: T::AccountId,
+        change: MembershipChange<T::AccountId>,
+    ) -> DispatchResult {
+        let open = <OpenProposals<T>>::get(tree);
+        ensure!(open < T::MaxProposals::get(), Error::<T>::TooManyProposals);
+        let index = <ProposalCount<T>>::get(tree);
+        let expires = <frame_system::Module<T>>::block_number()
+            + T::ProposalLifetime::get();
+        <Proposals<T>>::insert(
+            tree,
+            index,
+            Proposal {
+                change,
+                proposer: proposer.clone(),
+                approvals: vec![proposer.clone()],
+                expires,
+            },
+        );
+        <ProposalCount<T>>::insert(tree, index + 1);
+        <OpenProposals<T>>::insert(tree, open + 1);
+        Self::deposit_event(RawEvent::ProposalCreated(tree, index, proposer));
+        Ok(())
+    }
+    /// Close Proposal
+    /// -> removes an executed or cancelled proposal and frees its slot
+    fn close_proposal(tree: T::TreeId, index: u32) {
+        <Proposals<T>>::remove(tree, index);
+        <OpenProposals<T>>::mutate(tree, |open| *open = open.saturating_sub(1));
+    }
+    /// Add Members
+    /// -> reserves the member bond from the caller, who must be authorized
+    fn do_add_members(
+        caller: T::AccountId,
+        tree: TreeSt<T>,
+        members: MemberSet<T>,
+    ) -> DispatchResult {
+        let id = tree.id;
+        let mut mems = members;
+        mems.retain(|m| {
+            !<Members<T>>::contains_key(id, m)
+                && !<PendingInvites<T>>::contains_key(id, m)
+        });
+        // no bond without an invite to refund it
+        if mems.is_empty() {
+            return Ok(())
+        }
+        // pending invites hold their place until answered or expired
+        let now = <frame_system::Module<T>>::block_number();
+        let pending = <PendingInvites<T>>::iter_prefix(id)
+            .filter(|(_, i)| i.expires > now)
+            .count() as u32;
+        let new_size = tree.size + pending + mems.len();
+        ensure!(
+            new_size <= T::MaxSize::get(),
+            Error::<T>::CannotAddGroupAboveMaxSize
+        );
+        let bond = T::BondPolicy::member_bond(new_size)?;
+        Self::reserve_inviter_bond(id, &caller, bond)?;
+        let mems = mems.into_vec();
+        Self::invite(id, &caller, mems.clone(), bond);
+        Self::deposit_event(RawEvent::MembersInvited(caller, id, mems, bond));
+        Ok(())
+    }
+    /// Invite Members
+    /// -> records an invite per account, which splits `bond` evenly with
+    /// the remainder on the first invite
+    fn invite(
+        tree: T::TreeId,
+        inviter: &T::AccountId,
+        mems: Vec<T::AccountId>,
+        bond: BalanceOf<T>,
+    ) {
+        if mems.is_empty() {
+            return
+        }
+        let n: BalanceOf<T> = (mems.len() as u32).into();
+        let share = bond / n;
+        let mut first = share + bond % n;
+        let expires = <frame_system::Module<T>>::block_number()
+            + T::InviteLifetime::get();
+        mems.into_iter().for_each(|m| {
+            <PendingInvites<T>>::insert(
+                tree,
+                m,
+                Invite {
+                    inviter: inviter.clone(),
+                    bond: first,
+                    expires,
+                },
+            );
+            first = share;
+        });
+    }
+    /// Refund Invite
+    /// -> returns the invite's share of the bond to the inviter, at most the
+    /// bond still recorded for the inviter, and returns the amount refunded
+    fn refund_invite(tree: T::TreeId, invite: &InviteOf<T>) -> BalanceOf<T> {
+        let held = <InviterBonds<T>>::get(tree, &invite.inviter);
+        let refund = invite.bond.min(held);
+        Self::unreserve(&invite.inviter, refund);
+        if held == refund {
+            <InviterBonds<T>>::remove(tree, &invite.inviter);
+        } else {
+            <InviterBonds<T>>::insert(tree, &invite.inviter, held - refund);
+        }
+        refund
+    }
+    /// Remove Members
+    /// -> the caller must be authorized
+    fn do_remove_members(
+        caller: T::AccountId,
+        tree: TreeSt<T>,
+        members: MemberSet<T>,
+        penalty: bool,
+    ) {
+        let id = tree.id;
+        let mut removed = members;
+        removed
+            .retain(|m| tree.bonded != *m && <Members<T>>::contains_key(id, m));
+        let removed = removed.into_vec();
+        let (_, _, released) =
+            Self::remove_mems(tree, Some(removed.clone()), penalty);
+        Self::deposit_event(RawEvent::RemovedMembers(
+            caller, id, removed, released,
+        ));
+    }
+    /// Reserve Inviter Bond
+    /// -> reserves `bond` from the inviter and records it against the
+    /// inviter's invites to the tree
+    pub fn reserve_inviter_bond(
+        tree: T::TreeId,
+        inviter: &T::AccountId,
+        bond: BalanceOf<T>,
+    ) -> DispatchResult {
+        Self::reserve(inviter, bond)?;
+        <InviterBonds<T>>::mutate(tree, inviter, |b| {
+            *b = b.saturating_add(bond)
+        });
+        Ok(())
     }
     /// Add Members to Tree
-    pub fn add_mems(mut tree: TreeSt<T>, mut mems: Vec<T::AccountId>) {
-        mems.dedup();
-        let mut size_increase = 0u32;
+    /// -> returns the accounts that were not already members
+    pub fn add_mems(
+        mut tree: TreeSt<T>,
+        mems: Vec<T::AccountId>,
+    ) -> Vec<T::AccountId> {
+        let mut added = Vec::new();
         mems.into_iter().for_each(|m| {
             // only insert if profile does not already exist
             if <Members<T>>::get(tree.id, &m).is_none() {
-                <Members<T>>::insert(tree.id, m, BalanceOf::<T>::zero());
-                size_increase += 1u32;
+                <Members<T>>::insert(tree.id, &m, Membership::default());
+                added.push(m);
             }
         });
         // insert actual size increase
-        tree.size += size_increase;
+        tree.size += added.len() as u32;
         <Trees<T>>::insert(tree.id, tree);
+        added
+    }
+    /// Release Bond
+    /// -> returns the bond to the account or, if `penalty`, slashes the
+    /// reserved bond and hands it to `Trait::Slash`
+    pub fn release_bond(
+        tree: T::TreeId,
+        account: &T::AccountId,
+        bond: BalanceOf<T>,
+        penalty: bool,
+    ) {
+        if bond.is_zero() {
+            return
+        }
+        if penalty {
+            let (imbalance, _) = T::Currency::slash_reserved(account, bond);
+            Self::untrack(account, bond);
+            let slashed = imbalance.peek();
+            T::Slash::on_unbalanced(imbalance);
+            Self::deposit_event(RawEvent::Slashed(
+                tree,
+                account.clone(),
+                slashed,
+            ));
+        } else {
+            Self::unreserve(account, bond);
+        }
+    }
+    /// Reserve
+    /// -> reserves `bond` from the account and adds it to the account's
+    /// total, failing if the total would exceed `Trait::MaxBondPerAccount`
+    fn reserve(account: &T::AccountId, bond: BalanceOf<T>) -> DispatchResult {
+        let total = Self::account_bond(account)
+            .checked_add(&bond)
+            .ok_or(Error::<T>::BondOverflow)?;
+        ensure!(
+            total <= T::MaxBondPerAccount::get(),
+            Error::<T>::BondAboveMaxPerAccount
+        );
+        T::Currency::reserve(account, bond)?;
+        <AccountBonds<T>>::insert(account, total);
+        Ok(())
+    }
+    /// Unreserve
+    /// -> returns `bond` to the account and removes it from the account's
+    /// total
+    fn unreserve(account: &T::AccountId, bond: BalanceOf<T>) {
+        T::Currency::unreserve(account, bond);
+        Self::untrack(account, bond);
+    }
+    /// Untrack
+    /// -> removes `bond` from the account's total once it is no longer
+    /// reserved
+    fn untrack(account: &T::AccountId, bond: BalanceOf<T>) {
+        let total = Self::account_bond(account).saturating_sub(bond);
+        if total.is_zero() {
+            <AccountBonds<T>>::remove(account);
+        } else {
+            <AccountBonds<T>>::insert(account, total);
+        }
+    }
+    /// Disown
+    /// -> removes the root `id` from the roots registered by the account
+    fn disown(account: &T::AccountId, id: T::TreeId) {
+        let mut owned = Self::owned_trees(account);
+        owned.retain(|t| *t != id);
+        if owned.is_empty() {
+            <OwnedTrees<T>>::remove(account);
+        } else {
+            <OwnedTrees<T>>::insert(account, owned);
+        }
+    }
+    /// Relink Subtree
+    /// -> moves the subtree, root first, under `parent` at `height` and
+    /// shifts the height of every descendant by the same number of levels
+    fn relink(subtree: Vec<TreeSt<T>>, parent: T::TreeId, height: u32) {
+        let old_height = if let Some(root) = subtree.first() {
+            root.height
+        } else {
+            return
+        };
+        subtree.into_iter().enumerate().for_each(|(i, mut t)| {
+            if i == 0 {
+                if let Some(p) = t.parent {
+                    <Kids<T>>::remove(p, t.id);
+                    if let Some(tp) = <Trees<T>>::get(p) {
+                        <Trees<T>>::insert(
+                            p,
+                            TreeState {
+                                kids: tp.kids - 1,
+                                ..tp
+                            },
+                        );
+                    }
+                }
+                <Kids<T>>::insert(parent, t.id, ());
+                t.parent = Some(parent);
+            }
+            t.height = t.height - old_height + height;
+            <Trees<T>>::insert(t.id, t);
+        });
+    }
+    /// Transfer Ownership
+    /// -> moves `bonded` and the owner's bond to `new_owner`, the bonds
+    /// recorded in the membership of roots and for their invites and the
+    /// delegation bond of subtrees
+    fn transfer_bond(
+        mut tree: TreeSt<T>,
+        new_owner: T::AccountId,
+    ) -> DispatchResult {
+        let old_owner = tree.bonded.clone();
+        let is_root = tree.parent.is_none();
+        ensure!(
+            !is_root
+                || (Self::owned_trees(&new_owner).len() as u32)
+                    < T::MaxRootsPerAccount::get(),
+            Error::<T>::TooManyRoots
+        );
+        let moved = if is_root {
+            let bond = <Members<T>>::get(tree.id, &old_owner).map(|m| m.bond);
+            // only the owner invites to a root, its invites move with it
+            let invites = <InviterBonds<T>>::get(tree.id, &old_owner);
+            let total = bond.unwrap_or_else(Zero::zero).saturating_add(invites);
+            Self::reserve(&new_owner, total)?;
+            Self::unreserve(&old_owner, total);
+            if !invites.is_zero() {
+                <InviterBonds<T>>::remove(tree.id, &old_owner);
+                <InviterBonds<T>>::mutate(tree.id, &new_owner, |b| {
+                    *b = b.saturating_add(invites)
+                });
+                <PendingInvites<T>>::iter_prefix(tree.id)
+                    .filter(|(_, i)| i.inviter == old_owner)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .for_each(|(a, mut i)| {
+                        i.inviter = new_owner.clone();
+                        <PendingInvites<T>>::insert(tree.id, a, i);
+                    });
+            }
+            if let Some(b) = bond {
+                <Members<T>>::remove(tree.id, &old_owner);
+                // the new owner delegates like the previous one
+                let mut membership = Membership {
+                    bond: b,
+                    role: Role::Admin,
+                };
+                if let Some(m) = <Members<T>>::get(tree.id, &new_owner) {
+                    membership.bond += m.bond;
+                    tree.size -= 1;
+                }
+                <Members<T>>::insert(tree.id, &new_owner, membership);
+            }
+            Self::disown(&old_owner, tree.id);
+            <OwnedTrees<T>>::append(&new_owner, tree.id);
+            total
+        } else {
+            // members of subtrees keep their memberships
+            let bond = <DelegationBonds<T>>::get(tree.id);
+            Self::reserve(&new_owner, bond)?;
+            Self::unreserve(&old_owner, bond);
+            bond
+        };
+        Self::deposit_event(RawEvent::OwnershipTransferred(
+            tree.id,
+            old_owner,
+            new_owner.clone(),
+            moved,
+        ));
+        tree.bonded = new_owner;
+        <Trees<T>>::insert(tree.id, tree);
+        Ok(())
+    }
+    /// Remove Tree
+    /// -> removes the tree and its members with their bonds, the inviters'
+    /// bonds and the owner's delegation bond, and unlinks it from its parent,
+    /// leaving its kids in place; returns the number of members removed and
+    /// the bond released
+    pub fn remove_tree(tree: TreeSt<T>, penalty: bool) -> (u32, BalanceOf<T>) {
+        let mut size_decrease = 0u32;
+        let mut released: BalanceOf<T> = Zero::zero();
+        <Members<T>>::iter_prefix(tree.id).for_each(|(a, m)| {
+            Self::release_bond(tree.id, &a, m.bond, penalty);
+            released = released.saturating_add(m.bond);
+            <Members<T>>::remove(tree.id, a);
+            size_decrease += 1u32;
+        });
+        <InviterBonds<T>>::iter_prefix(tree.id).for_each(|(a, bond)| {
+            Self::release_bond(tree.id, &a, bond, penalty);
+            released = released.saturating_add(bond);
+        });
+        <InviterBonds<T>>::remove_prefix(tree.id);
+        // the owner's bond for delegating the tree
+        let bond = <DelegationBonds<T>>::take(tree.id);
+        Self::release_bond(tree.id, &tree.bonded, bond, penalty);
+        released = released.saturating_add(bond);
+        // if parent exists, decrement parent kids count
+        if tree.parent.is_none() {
+            Self::disown(&tree.bonded, tree.id);
+        }
+        if let Some(p) = tree.parent {
+            <Kids<T>>::remove(p, tree.id);
+            if let Some(tp) = <Trees<T>>::get(p) {
+                <Trees<T>>::insert(
+                    p,
+                    TreeState {
+                        kids: tp.kids - 1,
+                        ..tp
+                    },
+                );
+            }
+        }
+        <PendingTransfers<T>>::remove(tree.id);
+        <ManagerLevels<T>>::remove(tree.id);
+        <Thresholds<T>>::remove(tree.id);
+        <Proposals<T>>::remove_prefix(tree.id);
+        <ProposalCount<T>>::remove(tree.id);
+        <OpenProposals<T>>::remove(tree.id);
+        // invite bonds are released with the inviters' bonds
+        <PendingInvites<T>>::remove_prefix(tree.id);
+        <FrozenTrees<T>>::remove(tree.id);
+        if let Some(metadata) = <TreeMetadata<T>>::take(tree.id) {
+            T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+        }
+        <Trees<T>>::remove(tree.id);
+        Self::deposit_event(RawEvent::SubtreeRemoved(
+            tree.id,
+            size_decrease,
+            released,
+        ));
+        (size_decrease, released)
     }
     /// Remove Members of Tree
+    /// -> returns the number of (trees, members) removed and the bond
+    /// released
     pub fn remove_mems(
         mut tree: TreeSt<T>,
         mems: Option<Vec<T::AccountId>>,
         penalty: bool,
-    ) {
-        let mut size_decrease = 0u32;
-        if let Some(mut mem) = mems {
-            mem.dedup();
+    ) -> (u32, u32, BalanceOf<T>) {
+        if let Some(mem) = mems {
+            let mut size_decrease = 0u32;
+            let mut released: BalanceOf<T> = Zero::zero();
             mem.into_iter().for_each(|m| {
-                if let Some(bond) = <Members<T>>::get(tree.id, &m) {
+                if let Some(membership) = <Members<T>>::get(tree.id, &m) {
                     // constraint: cannot remove the account who created the hierarchy
                     if tree.bonded != m {
-                        T::Currency::unreserve(&m, bond);
-                        if penalty {
-                            // (could) transfer the bond to some (treasury) account
-                            // instead of returning the bond
-                            todo!();
-                        }
+                        Self::release_bond(
+                            tree.id,
+                            &m,
+                            membership.bond,
+                            penalty,
+                        );
+                        released = released.saturating_add(membership.bond);
                         <Members<T>>::remove(tree.id, m);
                         size_decrease += 1u32;
                     }
@@ -411,39 +2009,27 @@ impl<T: Trait> Module<T> {
             // insert actual size decrease
             tree.size -= size_decrease;
             <Trees<T>>::insert(tree.id, tree);
+            (0u32, size_decrease, released)
         } else {
-            <Members<T>>::iter_prefix(tree.id).for_each(|(a, b)| {
-                T::Currency::unreserve(&a, b);
-                if penalty {
-                    // (could) transfer the bond to some (treasury) account
-                    // instead of returning the bond
-                    todo!();
-                }
-                <Members<T>>::remove(tree.id, a);
-                size_decrease += 1u32;
-            });
-            // if parent exists, decrement parent kids count
-            if let Some(p) = tree.parent {
-                if let Some(tp) = <Trees<T>>::get(p) {
-                    <Trees<T>>::insert(
-                        p,
-                        TreeState {
-                            kids: tp.kids - 1,
-                            ..tp
-                        },
-                    );
-                }
-            }
+            let id = tree.id;
+            let (size_decrease, released) = Self::remove_tree(tree, penalty);
             // Recursively remove all Children
             // runtime recursion bounded by module-level constraints on
             // * delegation depth/height (MaxDepth)
             // * children (subtrees) per tree (MaxKids)
             // * members (accounts) per tree (MaxSize)
-            <Trees<T>>::iter().for_each(|(_, child)| {
-                if child.parent == Some(tree.id) {
-                    Self::remove_mems(child, None, penalty);
-                }
-            });
+            Self::kid_list(id).into_iter().fold(
+                (1u32, size_decrease, released),
+                |(t, m, b), k| {
+                    if let Some(child) = <Trees<T>>::get(k) {
+                        let (ct, cm, cb) =
+                            Self::remove_mems(child, None, penalty);
+                        (t + ct, m + cm, b.saturating_add(cb))
+                    } else {
+                        (t, m, b)
+                    }
+                },
+            )
         }
     }
 }
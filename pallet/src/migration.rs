@@ -0,0 +1,197 @@
+//! Storage migrations
+//! -> run in `on_runtime_upgrade` until `StorageVersion` is current
+use crate::{
+    AccountBonds,
+    BalanceOf,
+    DelegationBonds,
+    InviterBonds,
+    Kids,
+    Members,
+    Membership,
+    OpenProposals,
+    OwnedTrees,
+    Proposals,
+    Releases,
+    Role,
+    StorageVersion,
+    Trait,
+    TreeState,
+    Trees,
+};
+use frame_support::{
+    storage::{
+        migration::{
+            put_storage_value,
+            StorageIterator,
+        },
+        IterableStorageDoubleMap,
+        IterableStorageMap,
+        StorageDoubleMap,
+        StorageMap,
+        StorageValue,
+    },
+    traits::Get,
+    weights::Weight,
+};
+use parity_scale_codec::{
+    Decode,
+    Encode,
+};
+use sp_runtime::traits::{
+    Saturating,
+    Zero,
+};
+use sp_std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::btree_map::BTreeMap,
+    prelude::*,
+};
+
+/// Layouts before `Releases::V1`
+pub mod v0 {
+    use super::*;
+
+    /// `TreeState` before delegations could expire
+    #[derive(
+        PartialEq, Eq, Clone, Encode, Decode, sp_runtime::RuntimeDebug,
+    )]
+    pub struct TreeState<TreeId, AccountId> {
+        pub id: TreeId,
+        pub parent: Option<TreeId>,
+        pub bonded: AccountId,
+        pub height: u32,
+        pub kids: u32,
+        pub size: u32,
+    }
+}
+
+/// Migrate Storage
+/// -> applies every migration after the stored version, returns the weight
+pub fn migrate<T: Trait>() -> Weight {
+    let mut weight = T::DbWeight::get().reads(1);
+    if StorageVersion::get() == Releases::V0 {
+        weight = weight.saturating_add(migrate_to_v1::<T>());
+    }
+    if StorageVersion::get() == Releases::V1 {
+        weight = weight.saturating_add(migrate_to_v2::<T>());
+    }
+    if StorageVersion::get() == Releases::V2 {
+        weight = weight.saturating_add(migrate_to_v3::<T>());
+    }
+    weight
+}
+
+/// V0 -> V1
+/// -> adds `expires: None` to every `TreeState` and indexes every subtree
+/// under its parent in `Kids`, which trees delegated before the index
+/// are missing from
+fn migrate_to_v1<T: Trait>() -> Weight {
+    let translated = Cell::new(0u64);
+    let kids = RefCell::new(Vec::new());
+    <Trees<T>>::translate::<v0::TreeState<T::TreeId, T::AccountId>, _>(
+        |id, old| {
+            translated.set(translated.get() + 1);
+            if let Some(p) = old.parent {
+                kids.borrow_mut().push((p, id));
+            }
+            Some(TreeState {
+                id: old.id,
+                parent: old.parent,
+                bonded: old.bonded,
+                height: old.height,
+                kids: old.kids,
+                size: old.size,
+                expires: None,
+            })
+        },
+    );
+    let kids = kids.into_inner();
+    let indexed = kids.len() as u64;
+    kids.into_iter()
+        .for_each(|(p, id)| <Kids<T>>::insert(p, id, ()));
+    StorageVersion::put(Releases::V1);
+    let translated = translated.get();
+    T::DbWeight::get().reads_writes(translated, translated + indexed + 1)
+}
+
+/// V1 -> V2
+/// -> wraps every `Members` bond in a `Membership` with the admin role,
+/// which keeps the permissions members had before roles
+fn migrate_to_v2<T: Trait>() -> Weight {
+    let bonds = StorageIterator::<BalanceOf<T>>::new(b"Delegate", b"Members")
+        .collect::<Vec<_>>();
+    let translated = bonds.len() as u64;
+    bonds.into_iter().for_each(|(key, bond)| {
+        put_storage_value(
+            b"Delegate",
+            b"Members",
+            &key,
+            Membership {
+                bond,
+                role: Role::Admin,
+            },
+        );
+    });
+    StorageVersion::put(Releases::V2);
+    T::DbWeight::get().reads_writes(translated, translated + 1)
+}
+
+/// V2 -> V3
+/// -> indexes the roots registered by each account in `OwnedTrees`, the
+/// bonds reserved by each account in `AccountBonds` and the open proposals
+/// of each tree in `OpenProposals`; existing accounts and trees may exceed
+/// the new caps until they release trees, bonds or proposals
+fn migrate_to_v3<T: Trait>() -> Weight {
+    let mut reads = 0u64;
+    let mut roots: BTreeMap<T::AccountId, Vec<T::TreeId>> = BTreeMap::new();
+    <Trees<T>>::iter().for_each(|(id, tree)| {
+        reads += 1;
+        if tree.parent.is_none() {
+            roots.entry(tree.bonded).or_insert_with(Vec::new).push(id);
+        }
+    });
+    let mut bonds: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
+    <Members<T>>::iter().for_each(|(_, account, membership)| {
+        reads += 1;
+        let total = bonds.entry(account).or_insert_with(Zero::zero);
+        *total = total.saturating_add(membership.bond);
+    });
+    <InviterBonds<T>>::iter().for_each(|(_, account, bond)| {
+        reads += 1;
+        let total = bonds.entry(account).or_insert_with(Zero::zero);
+        *total = total.saturating_add(bond);
+    });
+    <DelegationBonds<T>>::iter().for_each(|(id, bond)| {
+        reads += 2;
+        if let Some(tree) = <Trees<T>>::get(id) {
+            let total = bonds.entry(tree.bonded).or_insert_with(Zero::zero);
+            *total = total.saturating_add(bond);
+        }
+    });
+    let mut open: BTreeMap<T::TreeId, u32> = BTreeMap::new();
+    <Proposals<T>>::iter().for_each(|(id, _, _)| {
+        reads += 1;
+        *open.entry(id).or_insert(0) += 1;
+    });
+    let mut writes = 1u64;
+    open.into_iter().for_each(|(id, count)| {
+        writes += 1;
+        <OpenProposals<T>>::insert(id, count);
+    });
+    roots.into_iter().for_each(|(account, ids)| {
+        writes += 1;
+        <OwnedTrees<T>>::insert(account, ids);
+    });
+    bonds
+        .into_iter()
+        .filter(|(_, bond)| !bond.is_zero())
+        .for_each(|(account, bond)| {
+            writes += 1;
+            <AccountBonds<T>>::insert(account, bond);
+        });
+    StorageVersion::put(Releases::V3);
+    T::DbWeight::get().reads_writes(reads, writes)
+}
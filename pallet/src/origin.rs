@@ -0,0 +1,76 @@
+//! Origin adapters
+//! -> gate other modules' dispatchables on tree membership or ownership
+use crate::{
+    Module,
+    Trait,
+    Trees,
+};
+use frame_support::traits::{
+    EnsureOrigin,
+    Get,
+};
+use frame_system::RawOrigin;
+use sp_std::marker::PhantomData;
+
+/// Ensures a signed origin that is a member of the tree `Id::get()`,
+/// returning `(TreeId, AccountId)`
+pub struct EnsureTreeMember<T, Id>(PhantomData<(T, Id)>);
+
+impl<O, T, Id> EnsureOrigin<O> for EnsureTreeMember<T, Id>
+where
+    O: Into<Result<RawOrigin<T::AccountId>, O>> + From<RawOrigin<T::AccountId>>,
+    T: Trait,
+    Id: Get<T::TreeId>,
+{
+    type Success = (T::TreeId, T::AccountId);
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        let tree = Id::get();
+        o.into().and_then(|o| {
+            match o {
+                RawOrigin::Signed(who)
+                    if Module::<T>::is_member(tree, &who) =>
+                {
+                    Ok((tree, who))
+                }
+                r => Err(O::from(r)),
+            }
+        })
+    }
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> O {
+        O::from(RawOrigin::Signed(Default::default()))
+    }
+}
+
+/// Ensures a signed origin that owns the tree `Id::get()`, returning
+/// `(TreeId, AccountId)`
+pub struct EnsureTreeOwner<T, Id>(PhantomData<(T, Id)>);
+
+impl<O, T, Id> EnsureOrigin<O> for EnsureTreeOwner<T, Id>
+where
+    O: Into<Result<RawOrigin<T::AccountId>, O>> + From<RawOrigin<T::AccountId>>,
+    T: Trait,
+    Id: Get<T::TreeId>,
+{
+    type Success = (T::TreeId, T::AccountId);
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        let tree = Id::get();
+        let owner = <Trees<T>>::get(tree)
+            .filter(|_| {
+                Module::<T>::is_active(tree) && !Module::<T>::is_frozen(tree)
+            })
+            .map(|t| t.bonded);
+        o.into().and_then(|o| {
+            match o {
+                RawOrigin::Signed(who) if owner.as_ref() == Some(&who) => {
+                    Ok((tree, who))
+                }
+                r => Err(O::from(r)),
+            }
+        })
+    }
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> O {
+        O::from(RawOrigin::Signed(Default::default()))
+    }
+}
@@ -0,0 +1,40 @@
+//! Bounded sets
+//! -> sorted lists without duplicates, for the members passed to
+//! `delegate`, `add_members` and `remove_members`
+use frame_support::traits::Get;
+use sp_std::{
+    marker::PhantomData,
+    prelude::*,
+};
+
+/// Sorted set of at most `Bound::get()` items
+pub struct BoundedSet<Item, Bound>(Vec<Item>, PhantomData<Bound>);
+
+impl<Item: Ord, Bound: Get<u32>> BoundedSet<Item, Bound> {
+    /// Sorts and dedupes `items`, `None` if more than `Bound::get()` remain
+    pub fn try_from_vec(mut items: Vec<Item>) -> Option<Self> {
+        items.sort();
+        items.dedup();
+        if items.len() as u32 > Bound::get() {
+            None
+        } else {
+            Some(BoundedSet(items, PhantomData))
+        }
+    }
+    pub fn len(&self) -> u32 {
+        self.0.len() as u32
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn contains(&self, item: &Item) -> bool {
+        self.0.binary_search(item).is_ok()
+    }
+    /// Keeps the items for which `f` holds, in order
+    pub fn retain<F: FnMut(&Item) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+    pub fn into_vec(self) -> Vec<Item> {
+        self.0
+    }
+}
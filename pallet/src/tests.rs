@@ -4,17 +4,26 @@ use super::*;
 use frame_support::{
     assert_noop,
     assert_ok,
+    impl_outer_dispatch,
     impl_outer_event,
     impl_outer_origin,
     parameter_types,
+    traits::{
+        EnsureOrigin,
+        Filter,
+        OnInitialize,
+        OnRuntimeUpgrade,
+    },
     weights::Weight,
 };
+use quickcheck::quickcheck;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::IdentityLookup,
     Perbill,
 };
+use std::collections::BTreeSet;
 
 pub type AccountId = u64;
 pub type BlockNumber = u64;
@@ -27,6 +36,14 @@ mod delegate {
     pub use super::super::*;
 }
 
+impl_outer_dispatch! {
+    pub enum Call for TestRuntime where origin: Origin {
+        frame_system::System,
+        pallet_balances::Balances,
+        delegate::Delegate,
+    }
+}
+
 impl_outer_event! {
     pub enum TestEvent for TestRuntime {
         frame_system<T>,
@@ -81,20 +98,68 @@ impl pallet_balances::Trait for TestRuntime {
     type AccountStore = System;
     type WeightInfo = ();
 }
+parameter_types! {
+    pub const TreasuryAccount: u64 = 100;
+}
+/// Pot that receives slashed bonds
+pub struct Treasury;
+impl OnUnbalanced<NegativeImbalanceOf<TestRuntime>> for Treasury {
+    fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<TestRuntime>) {
+        Balances::resolve_creating(&TreasuryAccount::get(), amount);
+    }
+}
 parameter_types! {
     pub const Bond: u64 = 2;
     pub const MaxSize: u32 = 5;
     pub const MaxDepth: u32 = 3;
     pub const MaxKids: u32 = 3;
+    pub const MaxExpiries: u32 = 2;
+    // two trees per block with the default weights
+    pub const RevocationBudget: Weight = 2_500_000_000;
+    pub const Degree: u32 = 2;
+    pub const Cap: u64 = 50;
+    pub const Engineering: u64 = 1;
+    pub const DelegateModuleId: ModuleId = ModuleId(*b"py/deleg");
+    pub const ProposalLifetime: u64 = 10;
+    pub const MaxProposals: u32 = 2;
+    pub const InviteLifetime: u64 = 5;
+    pub const MaxNameLength: u32 = 8;
+    pub const MaxUriLength: u32 = 16;
+    pub const MetadataDepositPerByte: u64 = 1;
+    pub const MaxRootsPerAccount: u32 = 2;
+    pub const MaxBondPerAccount: u64 = 2_000;
+}
+/// Trees may dispatch any call outside of this module
+pub struct NotDelegate;
+impl Filter<Call> for NotDelegate {
+    fn filter(call: &Call) -> bool {
+        !matches!(call, Call::Delegate(_))
+    }
 }
 impl Trait for TestRuntime {
     type Event = TestEvent;
     type TreeId = u64;
     type Bond = Bond;
+    type BondPolicy = ExponentialBond;
     type MaxSize = MaxSize;
     type MaxDepth = MaxDepth;
     type MaxKids = MaxKids;
+    type MaxExpiries = MaxExpiries;
+    type RevocationBudget = RevocationBudget;
     type Currency = Balances;
+    type Slash = Treasury;
+    type ModuleId = DelegateModuleId;
+    type Call = Call;
+    type CallFilter = NotDelegate;
+    type ProposalLifetime = ProposalLifetime;
+    type MaxProposals = MaxProposals;
+    type InviteLifetime = InviteLifetime;
+    type MaxNameLength = MaxNameLength;
+    type MaxUriLength = MaxUriLength;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type MaxRootsPerAccount = MaxRootsPerAccount;
+    type MaxBondPerAccount = MaxBondPerAccount;
+    type WeightInfo = ();
 }
 pub type System = frame_system::Module<TestRuntime>;
 pub type Balances = pallet_balances::Module<TestRuntime>;
@@ -115,7 +180,29 @@ fn get_last_event() -> RawEvent<u64, u64, u64> {
         .unwrap()
 }
 
-fn new_test_ext() -> sp_io::TestExternalities {
+/// Bond recorded for the member of the tree
+fn bond(tree: u64, account: u64) -> Option<u64> {
+    Delegate::members(tree, account).map(|m| m.bond)
+}
+
+/// Accept the invites of the members to the tree
+fn accept(tree: u64, members: Vec<u64>) {
+    members.into_iter().for_each(|m| {
+        assert_ok!(Delegate::accept_invite(Origin::signed(m), tree));
+    });
+}
+
+/// Invite members to the tree and accept every invite
+fn join(inviter: u64, tree: u64, members: Vec<u64>) {
+    assert_ok!(Delegate::add_members(
+        Origin::signed(inviter),
+        tree,
+        members.clone()
+    ));
+    accept(tree, members);
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
     let mut t = frame_system::GenesisConfig::default()
         .build_storage::<TestRuntime>()
         .unwrap();
@@ -178,7 +265,7 @@ fn base_case_revoke_works() {
         assert_eq!(RawEvent::RegisterIdRoot(0, 1, 2), get_last_event());
         assert_eq!(Balances::free_balance(&1), 998);
         assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
-        assert_eq!(RawEvent::RevokeDelegation(0), get_last_event());
+        assert_eq!(RawEvent::RevokeDelegation(0, 1, 1, 2), get_last_event());
         assert_eq!(Balances::free_balance(&1), 1000);
         for i in 2u64..7u64 {
             assert_eq!(Balances::free_balance(&i), 100);
@@ -189,7 +276,10 @@ fn base_case_revoke_works() {
             );
             assert_eq!(Balances::free_balance(&i), 98);
             assert_ok!(Delegate::revoke(Origin::signed(i), i - 1u64, false));
-            assert_eq!(RawEvent::RevokeDelegation(i - 1), get_last_event());
+            assert_eq!(
+                RawEvent::RevokeDelegation(i - 1, 1, 1, 2),
+                get_last_event()
+            );
             assert_eq!(Balances::free_balance(&i), 100);
         }
     });
@@ -208,11 +298,7 @@ fn add_remove_members_works() {
             Error::<TestRuntime>::CannotAddGroupAboveMaxSize
         );
         // 1 + 5 = 5 <= 5 Module Group Size Limit
-        assert_ok!(Delegate::add_members(
-            Origin::signed(1),
-            0,
-            vec![2, 3, 4, 5]
-        ));
+        join(1, 0, vec![2, 3, 4, 5]);
         // Linear collateral requirement for adding members
         // 998 - 2 * (new_size) = 998 - 2 * 5 = 988
         assert_eq!(Balances::free_balance(&1), 988);
@@ -240,34 +326,70 @@ fn delegate_works() {
         assert_ok!(Delegate::create_root(Origin::signed(1)));
         assert_eq!(RawEvent::RegisterIdRoot(0, 1, 2), get_last_event());
         assert_eq!(Balances::free_balance(&1), 998);
-        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3, 4]));
+        assert_ok!(Delegate::delegate(
+            Origin::signed(1),
+            0,
+            vec![2, 3, 4],
+            None
+        ));
         // 998 - bond ^ {height + kids} = 998 - 2 ^ {1 + 1}
         assert_eq!(Balances::free_balance(&1), 994);
-        assert_eq!(RawEvent::DelegateBranch(0, 1, 1, 4), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![3, 4, 6]));
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 1, 1, vec![2, 3, 4], 4),
+            get_last_event()
+        );
+        // initial members are invited and delegate once they accept
+        assert_eq!(Delegate::trees(1).unwrap().size, 0);
+        assert_eq!(Delegate::pending_invites(1, 2).map(|i| i.bond), Some(0));
+        assert_noop!(
+            Delegate::delegate(Origin::signed(2), 1, vec![3, 5], None),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        accept(1, vec![2, 3, 4]);
+        assert_eq!(Delegate::trees(1).unwrap().size, 3);
+        assert_ok!(Delegate::delegate(
+            Origin::signed(1),
+            0,
+            vec![3, 4, 6],
+            None
+        ));
         // 994 - bond ^ {height + kids} = 994 - 2 ^ {1 + 2}
         assert_eq!(Balances::free_balance(&1), 986);
-        assert_eq!(RawEvent::DelegateBranch(0, 2, 1, 8), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3, 5]));
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 2, 1, vec![3, 4, 6], 8),
+            get_last_event()
+        );
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3, 5], None));
         // 100 - bond ^ {height + kids} = 100 - 2 ^ {2 + 1}
         assert_eq!(Balances::free_balance(&2), 92);
-        assert_eq!(RawEvent::DelegateBranch(1, 3, 2, 8), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(3), 3, vec![1, 2]));
+        assert_eq!(
+            RawEvent::DelegateBranch(1, 3, 2, vec![3, 5], 8),
+            get_last_event()
+        );
+        accept(3, vec![3]);
+        assert_ok!(Delegate::delegate(Origin::signed(3), 3, vec![1, 2], None));
         // 100 - bond ^ {height + kids} = 100 - 2 ^ {3 + 1}
         assert_eq!(Balances::free_balance(&3), 84);
-        assert_eq!(RawEvent::DelegateBranch(3, 4, 3, 16), get_last_event());
+        assert_eq!(
+            RawEvent::DelegateBranch(3, 4, 3, vec![1, 2], 16),
+            get_last_event()
+        );
+        accept(4, vec![2]);
         // DEPTH CONSTRAINT
         assert_noop!(
-            Delegate::delegate(Origin::signed(2), 4, vec![5, 6]),
+            Delegate::delegate(Origin::signed(2), 4, vec![5, 6], None),
             Error::<TestRuntime>::CannotDelegateBelowMaxDepth
         );
-        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![5, 6]));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![5, 6], None));
         // 986 - bond ^ {height + kids} = 986 - 2 ^ {1 + 3}
         assert_eq!(Balances::free_balance(&1), 970);
-        assert_eq!(RawEvent::DelegateBranch(0, 5, 1, 16), get_last_event());
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 5, 1, vec![5, 6], 16),
+            get_last_event()
+        );
         // SPAN CONSTRAINT
         assert_noop!(
-            Delegate::delegate(Origin::signed(1), 0, vec![2, 8]),
+            Delegate::delegate(Origin::signed(1), 0, vec![2, 8], None),
             Error::<TestRuntime>::CannotDelegateAboveMaxKids
         );
     });
@@ -279,33 +401,1520 @@ fn recursive_revoke_works() {
         assert_ok!(Delegate::create_root(Origin::signed(1)));
         assert_eq!(RawEvent::RegisterIdRoot(0, 1, 2), get_last_event());
         assert_eq!(Balances::free_balance(&1), 998);
-        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3, 4]));
+        assert_ok!(Delegate::delegate(
+            Origin::signed(1),
+            0,
+            vec![2, 3, 4],
+            None
+        ));
         // 998 - bond ^ {height + kids} = 998 - 2 ^ {1 + 1}
         assert_eq!(Balances::free_balance(&1), 994);
-        assert_eq!(RawEvent::DelegateBranch(0, 1, 1, 4), get_last_event());
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 1, 1, vec![2, 3, 4], 4),
+            get_last_event()
+        );
         // 994 - bond ^ {height + kids} = 998 - 2 ^ {1 + 1}
         assert_eq!(Balances::free_balance(&1), 994);
-        assert_eq!(RawEvent::DelegateBranch(0, 1, 1, 4), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![3, 4, 6]));
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 1, 1, vec![2, 3, 4], 4),
+            get_last_event()
+        );
+        accept(1, vec![2, 3, 4]);
+        assert_ok!(Delegate::delegate(
+            Origin::signed(1),
+            0,
+            vec![3, 4, 6],
+            None
+        ));
         // 994 - bond ^ {height + kids} = 994 - 2 ^ {1 + 2}
         assert_eq!(Balances::free_balance(&1), 986);
-        assert_eq!(RawEvent::DelegateBranch(0, 2, 1, 8), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3, 5]));
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 2, 1, vec![3, 4, 6], 8),
+            get_last_event()
+        );
+        accept(2, vec![3, 4, 6]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3, 5], None));
         // 100 - bond ^ {height + kids} = 100 - 2 ^ {2 + 1}
         assert_eq!(Balances::free_balance(&2), 92);
-        assert_eq!(RawEvent::DelegateBranch(1, 3, 2, 8), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(3), 3, vec![1, 2]));
+        assert_eq!(
+            RawEvent::DelegateBranch(1, 3, 2, vec![3, 5], 8),
+            get_last_event()
+        );
+        accept(3, vec![3, 5]);
+        assert_ok!(Delegate::delegate(Origin::signed(3), 3, vec![1, 2], None));
         // 100 - bond ^ {height + kids} = 100 - 2 ^ {3 + 1}
         assert_eq!(Balances::free_balance(&3), 84);
-        assert_eq!(RawEvent::DelegateBranch(3, 4, 3, 16), get_last_event());
-        assert_ok!(Delegate::delegate(Origin::signed(5), 3, vec![1, 2]));
+        assert_eq!(
+            RawEvent::DelegateBranch(3, 4, 3, vec![1, 2], 16),
+            get_last_event()
+        );
+        accept(4, vec![1, 2]);
+        assert_ok!(Delegate::delegate(Origin::signed(5), 3, vec![1, 2], None));
         // 100 - bond ^ {height + kids} = 100 - 2 ^ {3 + 2}
         assert_eq!(Balances::free_balance(&5), 68);
-        assert_eq!(RawEvent::DelegateBranch(3, 5, 5, 32), get_last_event());
+        assert_eq!(
+            RawEvent::DelegateBranch(3, 5, 5, vec![1, 2], 32),
+            get_last_event()
+        );
+        accept(5, vec![1, 2]);
         assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        // 6 trees with 1 + 3 + 3 + 2 + 2 + 2 members, 2 + 4 + 8 + 8 + 16 + 32
+        // reserved in total
+        assert_eq!(RawEvent::RevokeDelegation(0, 6, 13, 70), get_last_event());
+        let mut removed = System::events()
+            .into_iter()
+            .filter_map(|r| {
+                match r.event {
+                    TestEvent::delegate(RawEvent::SubtreeRemoved(id, m, b)) => {
+                        Some((id, m, b))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![
+                (0, 1, 2),
+                (1, 3, 4),
+                (2, 3, 8),
+                (3, 2, 8),
+                (4, 2, 16),
+                (5, 2, 32)
+            ]
+        );
         assert_eq!(Balances::free_balance(&5), 100);
         assert_eq!(Balances::free_balance(&3), 100);
         assert_eq!(Balances::free_balance(&1), 1000);
         assert_eq!(Balances::free_balance(&2), 100);
     });
 }
+
+#[test]
+fn penalty_revoke_slashes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![4], None));
+        accept(2, vec![4]);
+        // 998 - 2 ^ {1 + 1} = 994
+        assert_eq!(Balances::free_balance(&1), 994);
+        // 100 - 2 ^ {2 + 1} = 92
+        assert_eq!(Balances::free_balance(&2), 92);
+        assert_eq!(Balances::free_balance(&TreasuryAccount::get()), 0);
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, true));
+        // reserved bonds are slashed instead of returned
+        assert_eq!(Balances::free_balance(&1), 994);
+        assert_eq!(Balances::reserved_balance(&1), 0);
+        assert_eq!(Balances::free_balance(&2), 92);
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        // 2 + 4 + 8 = 14
+        assert_eq!(Balances::free_balance(&TreasuryAccount::get()), 14);
+        assert_eq!(RawEvent::RevokeDelegation(0, 3, 4, 14), get_last_event());
+    });
+}
+
+#[test]
+fn penalty_remove_members_slashes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        join(1, 0, vec![2]);
+        // root size 2 => linear bond 2 * 2 = 4 reserved on top of 2 + 4
+        assert_eq!(Balances::reserved_balance(&1), 10);
+        // bonded owner is never removed, so nothing is slashed
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(1),
+            0,
+            vec![1],
+            true,
+        ));
+        assert_eq!(Balances::reserved_balance(&1), 10);
+        // the bond for delegating tree 2 stays with tree 2
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![4], None));
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(1),
+            0,
+            vec![2],
+            true,
+        ));
+        assert_eq!(
+            RawEvent::RemovedMembers(1, 0, vec![2], 0),
+            get_last_event()
+        );
+        assert_eq!(Balances::reserved_balance(&2), 8);
+        assert_ok!(Delegate::revoke(Origin::signed(2), 2, true));
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        // 2 ^ {1 + 2} = 8
+        assert_eq!(Balances::free_balance(&TreasuryAccount::get()), 8);
+        assert!(System::events().into_iter().any(|r| {
+            r.event == TestEvent::delegate(RawEvent::Slashed(2, 2, 8))
+        }));
+    });
+}
+
+#[test]
+fn revoke_walks_kids_index() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![3], None));
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![4], None));
+        assert_eq!(Delegate::kids(0, 1), Some(()));
+        assert_eq!(Delegate::kids(0, 2), Some(()));
+        assert_eq!(Delegate::kids(1, 3), Some(()));
+        assert_eq!(Delegate::kids(0, 3), None);
+        // revoking a subtree leaves its siblings in place
+        assert_ok!(Delegate::revoke(Origin::signed(1), 1, false));
+        assert!(Delegate::trees(1).is_none());
+        assert!(Delegate::trees(3).is_none());
+        assert_eq!(Delegate::kids(0, 1), None);
+        assert_eq!(Delegate::kids(1, 3), None);
+        assert_eq!(Delegate::kids(0, 2), Some(()));
+        assert_eq!(Delegate::trees(0).unwrap().kids, 1);
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        // revoked ids are not reused
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![5], None));
+        assert_eq!(Delegate::kids(0, 4), Some(()));
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert!(Delegate::trees(0).is_none());
+        assert!(Delegate::trees(2).is_none());
+        assert!(Delegate::trees(4).is_none());
+        assert_eq!(Delegate::kids(0, 2), None);
+        assert_eq!(Delegate::kids(0, 4), None);
+        assert_eq!(Balances::free_balance(&1), 1000);
+    });
+}
+
+#[test]
+fn subtree_bound_works() {
+    new_test_ext().execute_with(|| {
+        // MaxKids = 3, MaxDepth = 3, MaxSize = 5
+        assert_eq!(Delegate::subtree_bound(0), (1 + 3 + 9 + 27, 40 * 5));
+        assert_eq!(Delegate::subtree_bound(2), (1 + 3, 4 * 5));
+        assert_eq!(Delegate::subtree_bound(3), (1, 5));
+        assert_eq!(Delegate::subtree_bound(4), (1, 5));
+    });
+}
+
+#[test]
+fn revoke_refunds_unused_weight() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        let post = Delegate::revoke(Origin::signed(1), 0, false).unwrap();
+        // 2 trees with 1 and 2 members
+        assert_eq!(post.actual_weight, Some(<() as WeightInfo>::revoke(2, 3)));
+        assert_eq!(post.actual_weight, Some(Delegate::removal_weight(2, 3)));
+        // unknown trees are weighed as roots with MaxSize members each
+        assert_eq!(
+            Delegate::revoke_weight(0),
+            <() as WeightInfo>::revoke(40, 200)
+        );
+    });
+}
+
+#[test]
+fn tree_queries_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![4], None));
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![5], None));
+        accept(3, vec![5]);
+        let mut mems = Delegate::member_list(1);
+        mems.sort();
+        assert_eq!(mems, vec![2, 3]);
+        let mut kids = Delegate::kid_list(0);
+        kids.sort();
+        assert_eq!(kids, vec![1, 2]);
+        assert!(Delegate::kid_list(2).is_empty());
+        assert_eq!(Delegate::ancestors(3), vec![1, 0]);
+        assert!(Delegate::ancestors(0).is_empty());
+        assert!(Delegate::ancestors(9).is_empty());
+        assert!(Delegate::is_member(3, &5));
+        assert!(!Delegate::is_member(3, &2));
+    });
+}
+
+#[test]
+fn expired_delegations_are_revoked() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_noop!(
+            Delegate::delegate(Origin::signed(1), 0, vec![2], Some(1)),
+            Error::<TestRuntime>::ExpiryNotInFuture
+        );
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], Some(5)));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3], None));
+        accept(2, vec![3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![4], Some(5)));
+        // MaxExpiries = 2 per block
+        assert_noop!(
+            Delegate::delegate(Origin::signed(1), 0, vec![5], Some(5)),
+            Error::<TestRuntime>::TooManyExpiriesAtBlock
+        );
+        assert_eq!(Delegate::expiries(5), vec![1, 3]);
+        assert_eq!(Delegate::trees(1).unwrap().expires, Some(5));
+        // revoked before expiry
+        assert_ok!(Delegate::revoke(Origin::signed(1), 3, false));
+        System::set_block_number(4);
+        Delegate::on_initialize(4);
+        assert!(Delegate::trees(1).is_some());
+        System::set_block_number(5);
+        Delegate::on_initialize(5);
+        assert_eq!(RawEvent::DelegationExpired(1, 2, 2, 12), get_last_event());
+        assert!(Delegate::trees(1).is_none());
+        assert!(Delegate::trees(2).is_none());
+        assert!(Delegate::expiries(5).is_empty());
+        assert_eq!(Delegate::trees(0).unwrap().kids, 0);
+        // bonds of 2 ^ {1 + 1} and 2 ^ {2 + 1} for delegating trees 1 and 2
+        // are released
+        assert_eq!(Balances::free_balance(&1), 998);
+        assert_eq!(Balances::free_balance(&2), 100);
+    });
+}
+
+#[test]
+fn deferred_revoke_drains_over_blocks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3], None));
+        accept(2, vec![3]);
+        assert_ok!(Delegate::delegate(Origin::signed(3), 2, vec![4], None));
+        accept(3, vec![4]);
+        assert_noop!(
+            Delegate::revoke_deferred(Origin::signed(2), 0, false),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::revoke_deferred(Origin::signed(1), 0, false));
+        assert_eq!(RawEvent::RevocationScheduled(0), get_last_event());
+        // the mark blocks the whole subtree immediately
+        assert!(!Delegate::is_active(3));
+        assert!(!Delegate::is_member(3, &4));
+        assert_noop!(
+            Delegate::delegate(Origin::signed(4), 3, vec![5], None),
+            Error::<TestRuntime>::TreeRevoking
+        );
+        assert_noop!(
+            Delegate::add_members(Origin::signed(3), 3, vec![5]),
+            Error::<TestRuntime>::TreeRevoking
+        );
+        assert_noop!(
+            Delegate::revoke_deferred(Origin::signed(1), 0, false),
+            Error::<TestRuntime>::TreeRevoking
+        );
+        System::set_block_number(2);
+        Delegate::on_initialize(2);
+        assert_eq!(RawEvent::RevocationProgress(0, 1, 1), get_last_event());
+        assert!(Delegate::trees(0).is_none());
+        assert!(Delegate::trees(1).is_none());
+        assert!(Delegate::trees(2).is_some());
+        assert!(!Delegate::is_active(3));
+        assert_eq!(Delegate::pending_count(0), 1);
+        System::set_block_number(3);
+        Delegate::on_initialize(3);
+        assert_eq!(RawEvent::RevocationCompleted(0), get_last_event());
+        assert!(Delegate::trees(3).is_none());
+        assert_eq!(Delegate::pending_count(0), 0);
+        assert!(Delegate::pending_revocations(3).is_none());
+        assert_eq!(Balances::free_balance(&1), 1000);
+        assert_eq!(Balances::free_balance(&2), 100);
+        assert_eq!(Balances::free_balance(&3), 100);
+    });
+}
+
+#[test]
+fn deferred_revoke_keeps_pending_descendants() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3], None));
+        assert_ok!(Delegate::revoke_deferred(Origin::signed(2), 2, true));
+        assert_ok!(Delegate::revoke_deferred(Origin::signed(1), 1, false));
+        System::set_block_number(2);
+        Delegate::on_initialize(2);
+        // tree 2 drains under its own root whichever is drained first
+        assert_eq!(Delegate::pending_revocations(2), None);
+        assert_eq!(Delegate::pending_count(1), 0);
+        assert_eq!(Delegate::pending_count(2), 0);
+        let completed = System::events()
+            .into_iter()
+            .filter_map(|r| {
+                match r.event {
+                    TestEvent::delegate(RawEvent::RevocationCompleted(id)) => {
+                        Some(id)
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<BTreeSet<_>>();
+        assert_eq!(completed, vec![1, 2].into_iter().collect());
+        assert!(Delegate::trees(1).is_none());
+        assert!(Delegate::trees(2).is_none());
+    });
+}
+
+#[test]
+fn transfer_ownership_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        assert_noop!(
+            Delegate::accept_transfer(Origin::signed(2), 0),
+            Error::<TestRuntime>::NoPendingTransfer
+        );
+        assert_noop!(
+            Delegate::propose_transfer(Origin::signed(2), 0, 2),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::propose_transfer(Origin::signed(1), 0, 3));
+        assert_eq!(RawEvent::TransferProposed(0, 1, 3), get_last_event());
+        assert_noop!(
+            Delegate::accept_transfer(Origin::signed(2), 0),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        // 2 for the root + 2 ^ {1 + 1} for delegating tree 1
+        assert_eq!(Balances::reserved_balance(&1), 6);
+        assert_ok!(Delegate::accept_transfer(Origin::signed(3), 0));
+        assert_eq!(
+            RawEvent::OwnershipTransferred(0, 1, 3, 2),
+            get_last_event()
+        );
+        // 1 still owns tree 1
+        assert_eq!(Balances::reserved_balance(&1), 4);
+        assert_eq!(Balances::reserved_balance(&3), 2);
+        let tree = Delegate::trees(0).unwrap();
+        assert_eq!(tree.bonded, 3);
+        assert_eq!(tree.size, 1);
+        assert!(Delegate::members(0, 1).is_none());
+        assert_eq!(bond(0, 3), Some(2));
+        assert!(Delegate::pending_transfers(0).is_none());
+        // the new owner revokes and recovers the bond, subtrees intact
+        assert_eq!(Delegate::kids(0, 1), Some(()));
+        assert_noop!(
+            Delegate::revoke(Origin::signed(1), 0, false),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::revoke(Origin::signed(3), 0, false));
+        assert_eq!(Balances::free_balance(&3), 100);
+        assert_eq!(Balances::free_balance(&1), 1000);
+    });
+}
+
+#[test]
+fn transfer_subtree_moves_delegation_bond() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        // 2 ^ {1 + 1} for delegating tree 1
+        assert_eq!(Balances::reserved_balance(&2), 4);
+        assert_ok!(Delegate::propose_transfer(Origin::signed(2), 1, 4));
+        assert_ok!(Delegate::accept_transfer(Origin::signed(4), 1));
+        assert_eq!(
+            RawEvent::OwnershipTransferred(1, 2, 4, 4),
+            get_last_event()
+        );
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        assert_eq!(Balances::reserved_balance(&4), 4);
+        assert_eq!(Delegate::trees(1).map(|t| t.bonded), Some(4));
+        assert!(Delegate::owned_trees(4).is_empty());
+        // the previous owner stays a member of the subtree
+        assert!(Delegate::is_member(1, &2));
+        assert!(Delegate::members(1, 4).is_none());
+        assert_eq!(Delegate::trees(1).unwrap().size, 2);
+        assert_ok!(Delegate::revoke(Origin::signed(4), 1, false));
+        assert_eq!(Balances::free_balance(&4), 100);
+        assert_eq!(Balances::free_balance(&2), 100);
+    });
+}
+
+#[test]
+fn bond_policies_work() {
+    new_test_ext().execute_with(|| {
+        type Linear = LinearBond;
+        type Exponential = ExponentialBond;
+        type Capped = CappedPolynomialBond<Degree, Cap>;
+        assert_eq!(<Linear as BondPolicy<TestRuntime>>::member_bond(3), Ok(6));
+        assert_eq!(
+            <Linear as BondPolicy<TestRuntime>>::delegation_bond(2, 3),
+            Ok(10)
+        );
+        assert_eq!(
+            <Exponential as BondPolicy<TestRuntime>>::member_bond(3),
+            Ok(6)
+        );
+        assert_eq!(
+            <Exponential as BondPolicy<TestRuntime>>::delegation_bond(2, 3),
+            Ok(32)
+        );
+        // 2 * 3^2 and 2 * (2 + 3)^2 capped at 50
+        assert_eq!(<Capped as BondPolicy<TestRuntime>>::member_bond(3), Ok(18));
+        assert_eq!(
+            <Capped as BondPolicy<TestRuntime>>::delegation_bond(2, 3),
+            Ok(50)
+        );
+        assert_eq!(
+            <Capped as BondPolicy<TestRuntime>>::delegation_bond(
+                u32::max_value(),
+                1
+            ),
+            Ok(50)
+        );
+    });
+}
+
+#[test]
+fn bond_overflow_is_rejected() {
+    new_test_ext().execute_with(|| {
+        // 2^64 does not fit in u64
+        assert_eq!(
+            <ExponentialBond as BondPolicy<TestRuntime>>::delegation_bond(
+                60, 4
+            ),
+            Err(Error::<TestRuntime>::BondOverflow.into())
+        );
+        assert_eq!(
+            <ExponentialBond as BondPolicy<TestRuntime>>::delegation_bond(
+                60, 3
+            ),
+            Ok(1 << 63)
+        );
+        assert_eq!(
+            <LinearBond as BondPolicy<TestRuntime>>::delegation_bond(
+                u32::max_value(),
+                1
+            ),
+            Err(Error::<TestRuntime>::BondOverflow.into())
+        );
+    });
+}
+
+#[test]
+fn migration_to_v1_works() {
+    use frame_support::storage::{
+        unhashed,
+        StorageMap,
+    };
+    new_test_ext().execute_with(|| {
+        // pre-upgrade state, a root with one delegated subtree
+        let old = |id: u64, parent: Option<u64>, height, kids, size| {
+            migration::v0::TreeState {
+                id,
+                parent,
+                bonded: 1u64,
+                height,
+                kids,
+                size,
+            }
+        };
+        unhashed::put(
+            &<Trees<TestRuntime>>::hashed_key_for(0),
+            &old(0, None, 0, 1, 1),
+        );
+        unhashed::put(
+            &<Trees<TestRuntime>>::hashed_key_for(1),
+            &old(1, Some(0), 1, 0, 3),
+        );
+        assert_eq!(StorageVersion::get(), Releases::V0);
+        assert!(Delegate::on_runtime_upgrade() > 0);
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        // subtrees delegated before the index are indexed under their parent
+        assert_eq!(Delegate::kids(0, 1), Some(()));
+        assert_eq!(
+            Delegate::trees(0),
+            Some(TreeState {
+                id: 0,
+                parent: None,
+                bonded: 1,
+                height: 0,
+                kids: 1,
+                size: 1,
+                expires: None,
+            })
+        );
+        assert_eq!(
+            Delegate::trees(1),
+            Some(TreeState {
+                id: 1,
+                parent: Some(0),
+                bonded: 1,
+                height: 1,
+                kids: 0,
+                size: 3,
+                expires: None,
+            })
+        );
+        // already migrated trees are left as they are
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        Delegate::on_runtime_upgrade();
+        assert_eq!(Delegate::trees(2).map(|t| t.bonded), Some(2));
+        assert_eq!(Delegate::trees(1).map(|t| t.size), Some(3));
+    });
+}
+
+fn genesis_ext(
+    trees: Vec<(u64, Option<u64>, u64, Vec<u64>)>,
+) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+        .build_storage::<TestRuntime>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<TestRuntime> {
+        balances: vec![(1, 1000), (2, 100), (3, 100)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    GenesisConfig::<TestRuntime> { trees }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    t.into()
+}
+
+#[test]
+fn genesis_trees_work() {
+    genesis_ext(vec![
+        (0, None, 1, vec![1, 2, 3, 2]),
+        (1, Some(0), 2, vec![4, 5]),
+        (4, Some(0), 3, vec![6]),
+    ])
+    .execute_with(|| {
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        assert_eq!(Delegate::tree_id_counter(), 5);
+        let root = Delegate::trees(0).unwrap();
+        assert_eq!((root.bonded, root.kids, root.size), (1, 2, 3));
+        // create_root + add_members: 2 + 2 * 3
+        assert_eq!(bond(0, 1), Some(2));
+        assert_eq!(Delegate::inviter_bond(0, 1), 6);
+        assert_eq!(Balances::reserved_balance(&1), 8);
+        // delegate: 2^{1 + 1} and 2^{1 + 2}
+        assert_eq!(Delegate::delegation_bond(1), 4);
+        assert_eq!(Delegate::delegation_bond(4), 8);
+        assert_eq!(Balances::reserved_balance(&2), 4);
+        assert_eq!(bond(0, 2), Some(0));
+        let tree = Delegate::trees(1).unwrap();
+        assert_eq!((tree.parent, tree.height, tree.size), (Some(0), 1, 2));
+        assert_eq!(Delegate::member_list(4), vec![6]);
+        assert_eq!(Delegate::kids(0, 4), Some(()));
+        // ids continue after the highest genesis tree
+        System::set_block_number(1);
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        assert_eq!(RawEvent::RegisterIdRoot(5, 2, 2), get_last_event());
+    });
+}
+
+#[test]
+#[should_panic(expected = "genesis height above MaxDepth")]
+fn genesis_trees_respect_max_depth() {
+    genesis_ext(vec![
+        (0, None, 1, vec![]),
+        (1, Some(0), 1, vec![1]),
+        (2, Some(1), 1, vec![1]),
+        (3, Some(2), 1, vec![1]),
+        (4, Some(3), 1, vec![1]),
+    ]);
+}
+
+#[test]
+fn move_subtree_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![4], None));
+        accept(2, vec![4]);
+        assert_ok!(Delegate::delegate(Origin::signed(4), 2, vec![5], None));
+        // 2^{1 + 2} for delegating tree 2
+        assert_eq!(Delegate::delegation_bond(2), 8);
+        assert_eq!(Balances::free_balance(&2), 92);
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(1), 0, 1),
+            Error::<TestRuntime>::CannotMoveRoot
+        );
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(1), 2, 0),
+            Error::<TestRuntime>::AlreadyKidOfParent
+        );
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(1), 2, 3),
+            Error::<TestRuntime>::MoveCreatesCycle
+        );
+        // 1 is not a member of tree 1
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(1), 2, 1),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        // 3 is a member of both parents but does not own tree 2
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(3), 2, 1),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::move_subtree(Origin::signed(2), 2, 1));
+        // 2^{2 + 1} under tree 1 replaces 2^{1 + 2} under the root
+        assert_eq!(RawEvent::SubtreeMoved(2, 0, 1, 2, 8), get_last_event());
+        assert_eq!(Delegate::delegation_bond(2), 8);
+        assert_eq!(bond(0, 2), Some(0));
+        assert_eq!(Balances::free_balance(&2), 92);
+        assert_eq!(Delegate::kids(0, 2), None);
+        assert_eq!(Delegate::kids(1, 2), Some(()));
+        assert_eq!(Delegate::trees(0).unwrap().kids, 1);
+        assert_eq!(Delegate::trees(1).unwrap().kids, 1);
+        let tree = Delegate::trees(2).unwrap();
+        assert_eq!((tree.parent, tree.height), (Some(1), 2));
+        assert_eq!(Delegate::trees(3).unwrap().height, 3);
+        assert_eq!(Delegate::ancestors(3), vec![2, 1, 0]);
+        // tree 1 now reaches MaxDepth and cannot move below the root's kids
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![1, 2], None));
+        accept(4, vec![1, 2]);
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(1), 1, 4),
+            Error::<TestRuntime>::CannotDelegateBelowMaxDepth
+        );
+        // the moved subtree is revoked with its new parent
+        assert_ok!(Delegate::revoke(Origin::signed(1), 1, false));
+        assert!(Delegate::trees(2).is_none());
+        assert!(Delegate::trees(3).is_none());
+        // only the bond for delegating tree 4 is still reserved
+        assert_eq!(Balances::free_balance(&2), 92);
+    });
+}
+
+#[test]
+fn move_subtree_settles_bond_with_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![2], None));
+        accept(2, vec![2]);
+        // 2^{2 + 1} for delegating tree 2 under tree 1
+        assert_eq!(Delegate::delegation_bond(2), 8);
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        join(2, 3, vec![3]);
+        // 3 cannot lock more of 2's funds by moving tree 2
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(3), 2, 3),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        let free = Balances::free_balance(&2);
+        // 2^{1 + 1} under root 3, the difference is returned
+        assert_ok!(Delegate::move_subtree(Origin::signed(2), 2, 3));
+        assert_eq!(RawEvent::SubtreeMoved(2, 1, 3, 2, 4), get_last_event());
+        assert_eq!(Delegate::delegation_bond(2), 4);
+        assert_eq!(Balances::free_balance(&2), free + 4);
+        // back under tree 1, the difference is reserved again
+        assert_ok!(Delegate::move_subtree(Origin::signed(2), 2, 1));
+        assert_eq!(RawEvent::SubtreeMoved(2, 3, 1, 2, 8), get_last_event());
+        assert_eq!(Delegate::delegation_bond(2), 8);
+        assert_eq!(Balances::free_balance(&2), free);
+        assert_eq!(Delegate::account_bond(2), 8 + 2 + 4);
+    });
+}
+
+#[test]
+fn ensure_tree_origins_work() {
+    type Member = EnsureTreeMember<TestRuntime, Engineering>;
+    type Owner = EnsureTreeOwner<TestRuntime, Engineering>;
+    new_test_ext().execute_with(|| {
+        assert!(Member::ensure_origin(Origin::signed(2)).is_err());
+        assert!(Owner::ensure_origin(Origin::signed(1)).is_err());
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2, 3], None));
+        accept(1, vec![2, 3]);
+        assert_eq!(Member::ensure_origin(Origin::signed(2)), Ok((1, 2)));
+        assert_eq!(Member::ensure_origin(Origin::signed(3)), Ok((1, 3)));
+        assert!(Member::ensure_origin(Origin::signed(1)).is_err());
+        assert!(Member::ensure_origin(Origin::root()).is_err());
+        assert!(Member::ensure_origin(Origin::none()).is_err());
+        assert_eq!(Owner::ensure_origin(Origin::signed(1)), Ok((1, 1)));
+        assert!(Owner::ensure_origin(Origin::signed(2)).is_err());
+        // no authority while the tree is being revoked
+        assert_ok!(Delegate::revoke_deferred(Origin::signed(1), 0, false));
+        assert!(Member::ensure_origin(Origin::signed(2)).is_err());
+        assert!(Owner::ensure_origin(Origin::signed(1)).is_err());
+    });
+}
+
+#[test]
+fn ancestors_within_manager_levels_are_authorized() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3], None));
+        accept(2, vec![3]);
+        assert!(Delegate::is_authorized(2, &2, 1));
+        assert!(!Delegate::is_authorized(2, &1, 1));
+        assert!(Delegate::is_authorized(2, &1, 2));
+        assert!(!Delegate::is_authorized(2, &3, 3));
+        assert_eq!(Delegate::manager_levels(2), 1);
+        assert_noop!(
+            Delegate::add_members(Origin::signed(1), 2, vec![4]),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::set_manager_levels(Origin::signed(1), 2, 2),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::set_manager_levels(Origin::signed(2), 2, 0),
+            Error::<TestRuntime>::InvalidManagerLevels
+        );
+        assert_noop!(
+            Delegate::set_manager_levels(Origin::signed(2), 2, 4),
+            Error::<TestRuntime>::InvalidManagerLevels
+        );
+        assert_ok!(Delegate::set_manager_levels(Origin::signed(2), 2, 2));
+        assert_eq!(RawEvent::ManagerLevelsSet(2, 2), get_last_event());
+        // tree 1 empties out, the root still manages tree 2
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(1),
+            1,
+            vec![2],
+            false
+        ));
+        assert!(Delegate::member_list(1).is_empty());
+        join(1, 2, vec![4]);
+        // the inviter's bond does not make it a member
+        assert!(Delegate::members(2, 1).is_none());
+        assert_eq!(Delegate::inviter_bond(2, 1), 4);
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(1),
+            2,
+            vec![3],
+            false
+        ));
+        assert!(Delegate::members(2, 3).is_none());
+        assert_eq!(bond(2, 4), Some(0));
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert_eq!(Delegate::manager_levels(2), 1);
+    });
+}
+
+#[test]
+fn dispatch_as_tree_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2]);
+        let account = Delegate::tree_account(0);
+        assert_ok!(Balances::transfer(Origin::signed(1), account, 50));
+        let pay = |value| {
+            Box::new(Call::Balances(pallet_balances::Call::transfer(3, value)))
+        };
+        assert_noop!(
+            Delegate::dispatch_as_tree(Origin::signed(3), 0, pay(10)),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::dispatch_as_tree(Origin::signed(2), 1, pay(10)),
+            Error::<TestRuntime>::TreeDNE
+        );
+        assert_noop!(
+            Delegate::dispatch_as_tree(
+                Origin::signed(2),
+                0,
+                Box::new(Call::Delegate(crate::Call::create_root()))
+            ),
+            Error::<TestRuntime>::CallFiltered
+        );
+        assert_ok!(Delegate::dispatch_as_tree(Origin::signed(2), 0, pay(10)));
+        assert_eq!(RawEvent::TreeDispatched(0, 2, Ok(())), get_last_event());
+        assert_eq!(Balances::free_balance(&account), 40);
+        assert_eq!(Balances::free_balance(&3), 110);
+        // failures of the inner call are reported in the event
+        assert_ok!(Delegate::dispatch_as_tree(Origin::signed(1), 0, pay(100)));
+        assert!(matches!(
+            get_last_event(),
+            RawEvent::TreeDispatched(0, 1, Err(_))
+        ));
+        assert_eq!(Balances::free_balance(&account), 40);
+    });
+}
+
+#[test]
+fn threshold_membership_changes_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![], None));
+        assert_noop!(
+            Delegate::set_threshold(Origin::signed(2), 1, Some(2)),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::set_threshold(Origin::signed(1), 0, Some(2)),
+            Error::<TestRuntime>::ThresholdOnRoot
+        );
+        assert_noop!(
+            Delegate::set_threshold(Origin::signed(1), 1, Some(0)),
+            Error::<TestRuntime>::InvalidThreshold
+        );
+        assert_noop!(
+            Delegate::set_threshold(Origin::signed(1), 1, Some(6)),
+            Error::<TestRuntime>::InvalidThreshold
+        );
+        assert_ok!(Delegate::set_threshold(Origin::signed(1), 1, Some(2)));
+        assert_eq!(RawEvent::ThresholdSet(1, Some(2)), get_last_event());
+        // 2 of the root's members must agree
+        assert_ok!(Delegate::add_members(Origin::signed(1), 1, vec![4, 5]));
+        assert_eq!(RawEvent::ProposalCreated(1, 0, 1), get_last_event());
+        assert!(Delegate::members(1, 4).is_none());
+        assert_noop!(
+            Delegate::approve_proposal(Origin::signed(1), 1, 0),
+            Error::<TestRuntime>::AlreadyApproved
+        );
+        assert_noop!(
+            Delegate::approve_proposal(Origin::signed(6), 1, 0),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        let free = Balances::free_balance(&1);
+        assert_ok!(Delegate::approve_proposal(Origin::signed(2), 1, 0));
+        assert_eq!(RawEvent::ProposalExecuted(1, 0), get_last_event());
+        assert_eq!(Delegate::pending_invites(1, 4).map(|i| i.inviter), Some(1));
+        assert_ok!(Delegate::accept_invite(Origin::signed(4), 1));
+        assert_ok!(Delegate::accept_invite(Origin::signed(5), 1));
+        assert_eq!(bond(1, 4), Some(0));
+        assert_eq!(Delegate::trees(1).unwrap().size, 2);
+        // the proposer reserves the bond for 2 members without joining
+        assert_eq!(Balances::free_balance(&1), free - 4);
+        assert_eq!(Delegate::inviter_bond(1, 1), 4);
+        assert!(Delegate::members(1, 1).is_none());
+        assert_noop!(
+            Delegate::approve_proposal(Origin::signed(3), 1, 0),
+            Error::<TestRuntime>::ProposalDNE
+        );
+        // proposals expire after ProposalLifetime
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(2),
+            1,
+            vec![4],
+            false
+        ));
+        assert_eq!(RawEvent::ProposalCreated(1, 1, 2), get_last_event());
+        assert_noop!(
+            Delegate::cancel_proposal(Origin::signed(3), 1, 1),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        System::set_block_number(11);
+        assert_noop!(
+            Delegate::approve_proposal(Origin::signed(3), 1, 1),
+            Error::<TestRuntime>::ProposalExpired
+        );
+        assert_ok!(Delegate::cancel_proposal(Origin::signed(3), 1, 1));
+        assert_eq!(RawEvent::ProposalCancelled(1, 1), get_last_event());
+        assert!(Delegate::proposals(1, 1).is_none());
+        // the proposer can cancel at any time
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(2),
+            1,
+            vec![4],
+            false
+        ));
+        assert_ok!(Delegate::cancel_proposal(Origin::signed(2), 1, 2));
+        // without a threshold changes apply immediately
+        assert_ok!(Delegate::set_threshold(Origin::signed(1), 1, None));
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(2),
+            1,
+            vec![4],
+            false
+        ));
+        assert!(Delegate::members(1, 4).is_none());
+        assert_eq!(Delegate::open_proposals(1), 0);
+        // at most MaxProposals are open at once
+        assert_ok!(Delegate::set_threshold(Origin::signed(1), 1, Some(3)));
+        assert_ok!(Delegate::add_members(Origin::signed(3), 1, vec![6]));
+        assert_ok!(Delegate::add_members(Origin::signed(2), 1, vec![6]));
+        assert_eq!(Delegate::open_proposals(1), 2);
+        assert_noop!(
+            Delegate::add_members(Origin::signed(3), 1, vec![5]),
+            Error::<TestRuntime>::TooManyProposals
+        );
+        assert_ok!(Delegate::cancel_proposal(Origin::signed(2), 1, 4));
+        assert_eq!(Delegate::open_proposals(1), 1);
+        // pending proposals are removed with the tree
+        assert_ok!(Delegate::revoke(Origin::signed(1), 1, false));
+        assert!(Delegate::proposals(1, 3).is_none());
+        assert_eq!(Delegate::open_proposals(1), 0);
+        assert!(Delegate::threshold(1).is_none());
+    });
+}
+
+#[test]
+fn migration_to_v2_works() {
+    use frame_support::storage::{
+        unhashed,
+        StorageDoubleMap,
+    };
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        // pre-upgrade state, bonds without roles
+        unhashed::put(&<Members<TestRuntime>>::hashed_key_for(0, 1), &2u64);
+        unhashed::put(&<Members<TestRuntime>>::hashed_key_for(0, 2), &0u64);
+        StorageVersion::put(Releases::V1);
+        assert!(Delegate::on_runtime_upgrade() > 0);
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        assert_eq!(
+            Delegate::members(0, 1),
+            Some(Membership {
+                bond: 2,
+                role: Role::Admin
+            })
+        );
+        assert_eq!(
+            Delegate::members(0, 2),
+            Some(Membership {
+                bond: 0,
+                role: Role::Admin
+            })
+        );
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert_eq!(Balances::free_balance(&1), 1000);
+    });
+}
+
+#[test]
+fn roles_restrict_delegation_and_management() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![4], None));
+        accept(1, vec![4]);
+        assert_eq!(Delegate::members(0, 2).map(|m| m.role), Some(Role::Admin));
+        assert_noop!(
+            Delegate::set_role(Origin::signed(2), 0, 3, Role::Member),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::set_role(Origin::signed(1), 0, 1, Role::Member),
+            Error::<TestRuntime>::CannotSetOwnerRole
+        );
+        assert_noop!(
+            Delegate::set_role(Origin::signed(1), 0, 5, Role::Member),
+            Error::<TestRuntime>::NotMember
+        );
+        assert_ok!(Delegate::set_role(
+            Origin::signed(1),
+            0,
+            2,
+            Role::Delegator
+        ));
+        assert_eq!(RawEvent::RoleSet(0, 2, Role::Delegator), get_last_event());
+        assert_ok!(Delegate::set_role(Origin::signed(1), 0, 3, Role::Member));
+        // plain members cannot delegate
+        assert_noop!(
+            Delegate::delegate(Origin::signed(3), 0, vec![5], None),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![5], None));
+        // only admins manage the membership of subtrees
+        assert!(!Delegate::is_authorized(1, &2, 1));
+        assert_noop!(
+            Delegate::add_members(Origin::signed(2), 1, vec![6]),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_noop!(
+            Delegate::set_role(Origin::signed(2), 1, 4, Role::Member),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::set_role(Origin::signed(1), 1, 4, Role::Member));
+        assert_noop!(
+            Delegate::delegate(Origin::signed(4), 1, vec![6], None),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        // a promoted member regains the permissions
+        assert_ok!(Delegate::set_role(Origin::signed(1), 0, 2, Role::Admin));
+        join(2, 1, vec![6]);
+        assert!(Delegate::is_member(1, &6));
+    });
+}
+
+#[test]
+fn leave_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        join(1, 0, vec![2, 3]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 0, vec![4], None));
+        accept(1, vec![4]);
+        assert_eq!(Balances::free_balance(&2), 96);
+        assert_noop!(
+            Delegate::leave(Origin::signed(5), 0),
+            Error::<TestRuntime>::NotMember
+        );
+        assert_noop!(
+            Delegate::leave(Origin::signed(1), 0),
+            Error::<TestRuntime>::OwnerCannotLeave
+        );
+        // owners of subtrees keep their delegation bonds when leaving
+        assert_ok!(Delegate::leave(Origin::signed(2), 0));
+        assert_eq!(RawEvent::Left(0, 2, 0), get_last_event());
+        assert!(!Delegate::is_member(0, &2));
+        assert_eq!(Delegate::trees(0).unwrap().size, 2);
+        assert_eq!(Balances::free_balance(&2), 96);
+        assert_ok!(Delegate::leave(Origin::signed(4), 1));
+        assert_eq!(Delegate::trees(1).unwrap().size, 0);
+        assert_ok!(Delegate::revoke(Origin::signed(2), 1, false));
+        assert_eq!(Balances::free_balance(&2), 100);
+        // the owner leaves once ownership is transferred
+        assert_ok!(Delegate::propose_transfer(Origin::signed(1), 0, 3));
+        assert_ok!(Delegate::accept_transfer(Origin::signed(3), 0));
+        join(3, 0, vec![1]);
+        assert_ok!(Delegate::leave(Origin::signed(1), 0));
+        assert_eq!(Balances::free_balance(&1), 1000);
+        // no leaving with the bond while a revocation is pending
+        join(3, 0, vec![5]);
+        assert_ok!(Delegate::revoke_deferred(Origin::signed(3), 0, true));
+        assert_noop!(
+            Delegate::leave(Origin::signed(5), 0),
+            Error::<TestRuntime>::TreeRevoking
+        );
+    });
+}
+
+#[test]
+fn invites_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![3, 2, 3]));
+        // 2 * 3 split between the two invites
+        assert_eq!(
+            RawEvent::MembersInvited(1, 0, vec![2, 3], 6),
+            get_last_event()
+        );
+        assert_eq!(
+            Delegate::pending_invites(0, 2),
+            Some(Invite {
+                inviter: 1,
+                bond: 3,
+                expires: 6
+            })
+        );
+        assert!(!Delegate::is_member(0, &2));
+        assert_eq!(Delegate::trees(0).unwrap().size, 1);
+        // pending invites count towards MaxSize
+        assert_noop!(
+            Delegate::add_members(Origin::signed(1), 0, vec![4, 5, 6]),
+            Error::<TestRuntime>::CannotAddGroupAboveMaxSize
+        );
+        assert_noop!(
+            Delegate::accept_invite(Origin::signed(4), 0),
+            Error::<TestRuntime>::InviteDNE
+        );
+        assert_ok!(Delegate::accept_invite(Origin::signed(2), 0));
+        assert_eq!(RawEvent::InviteAccepted(0, 2), get_last_event());
+        assert!(Delegate::is_member(0, &2));
+        assert_eq!(Delegate::trees(0).unwrap().size, 2);
+        assert_noop!(
+            Delegate::decline_invite(Origin::signed(2), 0),
+            Error::<TestRuntime>::InviteDNE
+        );
+        assert_ok!(Delegate::decline_invite(Origin::signed(3), 0));
+        assert_eq!(RawEvent::InviteDeclined(0, 3, 3), get_last_event());
+        assert_eq!(bond(0, 1), Some(2));
+        assert_eq!(Delegate::inviter_bond(0, 1), 3);
+        assert_eq!(Balances::free_balance(&1), 995);
+        // nothing is reserved without new invitees
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![2]));
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![]));
+        assert_eq!(Delegate::inviter_bond(0, 1), 3);
+        assert_eq!(Balances::free_balance(&1), 995);
+        // expired invites are cleared by anyone
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![4]));
+        assert_eq!(Balances::free_balance(&1), 989);
+        assert_noop!(
+            Delegate::cancel_invite(Origin::signed(2), 0, 4),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        System::set_block_number(6);
+        assert_noop!(
+            Delegate::accept_invite(Origin::signed(4), 0),
+            Error::<TestRuntime>::InviteExpired
+        );
+        // expired invites no longer count towards MaxSize
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![3, 5, 6]));
+        assert_eq!(Balances::free_balance(&1), 979);
+        assert_ok!(Delegate::cancel_invite(Origin::signed(2), 0, 4));
+        assert_eq!(RawEvent::InviteCancelled(0, 4, 6), get_last_event());
+        assert_eq!(Balances::free_balance(&1), 985);
+        // pending invites are removed with the tree
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert!(Delegate::pending_invites(0, 5).is_none());
+        assert_eq!(Balances::free_balance(&1), 1000);
+    });
+}
+
+#[test]
+fn metadata_deposit_works() {
+    new_test_ext().execute_with(|| {
+        let hash = H256::repeat_byte(1);
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        // 2 names tree 1 as a member of its parent
+        join(1, 0, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        let set = |who, tree, name: &[u8]| {
+            Delegate::set_metadata(
+                Origin::signed(who),
+                tree,
+                name.to_vec(),
+                b"ipfs://x".to_vec(),
+                hash,
+            )
+        };
+        assert_noop!(set(2, 0, b"oncall"), Error::<TestRuntime>::NotAuthorized);
+        assert_noop!(
+            set(1, 1, b"engineers"),
+            Error::<TestRuntime>::NameTooLong
+        );
+        assert_noop!(
+            Delegate::set_metadata(
+                Origin::signed(1),
+                1,
+                b"oncall".to_vec(),
+                b"ipfs://0123456789".to_vec(),
+                hash
+            ),
+            Error::<TestRuntime>::UriTooLong
+        );
+        // 6 + 8 + 32 bytes
+        assert_ok!(set(2, 1, b"oncall"));
+        assert_eq!(RawEvent::MetadataSet(1, 2, 46), get_last_event());
+        assert_eq!(Balances::reserved_balance(&2), 46);
+        assert_eq!(
+            Delegate::metadata(1).map(|m| m.name),
+            Some(b"oncall".to_vec())
+        );
+        // replacing the metadata refunds the previous depositor
+        assert_ok!(set(1, 1, b"pager"));
+        assert_eq!(Balances::reserved_balance(&2), 0);
+        assert_eq!(Delegate::metadata(1).map(|m| m.deposit), Some(45));
+        assert_ok!(set(1, 0, b"eng"));
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert!(Delegate::metadata(0).is_none());
+        assert!(Delegate::metadata(1).is_none());
+        assert_eq!(Balances::reserved_balance(&1), 0);
+        assert_eq!(Balances::free_balance(&1), 1000);
+    });
+}
+
+#[test]
+fn delegate_enforces_max_size() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_noop!(
+            Delegate::delegate(
+                Origin::signed(1),
+                0,
+                vec![2, 3, 4, 5, 6, 7],
+                None
+            ),
+            Error::<TestRuntime>::TooManyInitialMembers
+        );
+        // duplicates are counted once wherever they appear
+        assert_ok!(Delegate::delegate(
+            Origin::signed(1),
+            0,
+            vec![2, 3, 2, 4, 3, 5, 6, 2],
+            None
+        ));
+        assert_eq!(
+            RawEvent::DelegateBranch(0, 1, 1, vec![2, 3, 4, 5, 6], 4),
+            get_last_event()
+        );
+        accept(1, vec![2, 3, 4, 5, 6]);
+        assert_eq!(Delegate::trees(1).unwrap().size, 5);
+        assert_noop!(
+            Delegate::add_members(Origin::signed(1), 0, vec![2, 3, 4, 5, 6, 7]),
+            Error::<TestRuntime>::CannotAddGroupAboveMaxSize
+        );
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![2, 3, 2]));
+        assert_eq!(
+            RawEvent::MembersInvited(1, 0, vec![2, 3], 6),
+            get_last_event()
+        );
+        assert_noop!(
+            Delegate::remove_members(
+                Origin::signed(1),
+                1,
+                vec![2, 3, 4, 5, 6, 7],
+                false
+            ),
+            Error::<TestRuntime>::TooManyMembersToRemove
+        );
+        assert_ok!(Delegate::remove_members(
+            Origin::signed(1),
+            1,
+            vec![4, 2, 4, 9],
+            false
+        ));
+        assert_eq!(
+            RawEvent::RemovedMembers(1, 1, vec![2, 4], 0),
+            get_last_event()
+        );
+        assert_eq!(Delegate::trees(1).unwrap().size, 3);
+    });
+}
+
+#[test]
+fn account_caps_work() {
+    use frame_support::storage::StorageMap;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_noop!(
+            Delegate::create_root(Origin::signed(1)),
+            Error::<TestRuntime>::TooManyRoots
+        );
+        assert_eq!(Delegate::owned_trees(1), vec![0, 1]);
+        assert_eq!(Delegate::account_bond(1), 4);
+        // 2 ^ {1 + 1} for delegating tree 2
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        assert_eq!(Delegate::account_bond(1), 8);
+        // transferred roots count towards the new owner's cap
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        assert_ok!(Delegate::propose_transfer(Origin::signed(1), 1, 2));
+        assert_noop!(
+            Delegate::accept_transfer(Origin::signed(2), 1),
+            Error::<TestRuntime>::TooManyRoots
+        );
+        assert_ok!(Delegate::revoke(Origin::signed(2), 3, false));
+        assert_eq!(Delegate::owned_trees(2), vec![4]);
+        assert_eq!(Delegate::account_bond(2), 2);
+        assert_ok!(Delegate::accept_transfer(Origin::signed(2), 1));
+        assert_eq!(Delegate::owned_trees(1), vec![0]);
+        assert_eq!(Delegate::owned_trees(2), vec![4, 1]);
+        assert_eq!(Delegate::account_bond(1), 6);
+        assert_eq!(Delegate::account_bond(2), 4);
+        // revoking releases the root and every bond reserved below it
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert!(Delegate::owned_trees(1).is_empty());
+        assert_eq!(Delegate::account_bond(1), 0);
+        assert_eq!(Balances::free_balance(&1), 1000);
+        // an account close to the cap cannot reserve another bond
+        <AccountBonds<TestRuntime>>::insert(1, 1_999);
+        assert_noop!(
+            Delegate::create_root(Origin::signed(1)),
+            Error::<TestRuntime>::BondAboveMaxPerAccount
+        );
+        <AccountBonds<TestRuntime>>::insert(1, 1_998);
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_eq!(Delegate::account_bond(1), 2_000);
+    });
+}
+
+#[test]
+fn migration_to_v3_works() {
+    use frame_support::storage::StorageMap;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![3], None));
+        join(1, 0, vec![4]);
+        assert_ok!(Delegate::set_threshold(Origin::signed(1), 2, Some(2)));
+        assert_ok!(Delegate::add_members(Origin::signed(1), 2, vec![5]));
+        let owned = (Delegate::owned_trees(1), Delegate::owned_trees(2));
+        let bonds = (Delegate::account_bond(1), Delegate::account_bond(2));
+        assert_eq!(bonds, (10, 2));
+        // pre-upgrade state, no indexes
+        [1, 2].iter().for_each(|a| {
+            <OwnedTrees<TestRuntime>>::remove(a);
+            <AccountBonds<TestRuntime>>::remove(a);
+        });
+        <OpenProposals<TestRuntime>>::remove(2);
+        StorageVersion::put(Releases::V2);
+        assert!(Delegate::on_runtime_upgrade() > 0);
+        assert_eq!(StorageVersion::get(), Releases::V3);
+        assert_eq!((Delegate::owned_trees(1), Delegate::owned_trees(2)), owned);
+        assert_eq!(
+            (Delegate::account_bond(1), Delegate::account_bond(2)),
+            bonds
+        );
+        assert_eq!(Delegate::open_proposals(2), 1);
+        // members without bonds are not indexed
+        assert!(!<AccountBonds<TestRuntime>>::contains_key(3));
+        assert!(!<AccountBonds<TestRuntime>>::contains_key(4));
+    });
+}
+
+#[test]
+fn freeze_cascades_to_descendants() {
+    type Member = EnsureTreeMember<TestRuntime, Engineering>;
+    type Owner = EnsureTreeOwner<TestRuntime, Engineering>;
+    new_test_ext().execute_with(|| {
+        assert_ok!(Delegate::create_root(Origin::signed(1)));
+        assert_ok!(Delegate::delegate(Origin::signed(1), 0, vec![2], None));
+        accept(1, vec![2]);
+        assert_ok!(Delegate::delegate(Origin::signed(2), 1, vec![3], None));
+        accept(2, vec![3]);
+        // only the owner of the tree or of an ancestor freezes it
+        assert_noop!(
+            Delegate::freeze(Origin::signed(2), 1),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::freeze(Origin::signed(1), 1));
+        assert_eq!(RawEvent::TreeFrozen(1, 1), get_last_event());
+        assert_noop!(
+            Delegate::freeze(Origin::signed(1), 1),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_noop!(
+            Delegate::delegate(Origin::signed(2), 1, vec![4], None),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_noop!(
+            Delegate::delegate(Origin::signed(3), 2, vec![4], None),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_noop!(
+            Delegate::add_members(Origin::signed(1), 1, vec![4]),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_noop!(
+            Delegate::remove_members(Origin::signed(2), 2, vec![3], false),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert!(!Delegate::is_member(2, &3));
+        assert!(!Delegate::is_authorized(2, &2, 1));
+        assert!(Member::ensure_origin(Origin::signed(2)).is_err());
+        assert!(Owner::ensure_origin(Origin::signed(1)).is_err());
+        // the parent keeps working
+        assert_ok!(Delegate::add_members(Origin::signed(1), 0, vec![5]));
+        // descendants frozen on their own stay frozen
+        assert_ok!(Delegate::freeze(Origin::signed(2), 2));
+        assert_noop!(
+            Delegate::unfreeze(Origin::signed(2), 1),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(1), 1));
+        assert_eq!(RawEvent::TreeUnfrozen(1, 1), get_last_event());
+        assert_eq!(Member::ensure_origin(Origin::signed(2)), Ok((1, 2)));
+        assert_eq!(Owner::ensure_origin(Origin::signed(1)), Ok((1, 1)));
+        assert_noop!(
+            Delegate::delegate(Origin::signed(3), 2, vec![4], None),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(1), 2));
+        assert_noop!(
+            Delegate::unfreeze(Origin::signed(1), 2),
+            Error::<TestRuntime>::NotFrozen
+        );
+        assert_ok!(Delegate::delegate(Origin::signed(3), 2, vec![4], None));
+        // a subtree owner cannot lift a freeze set from above
+        assert_ok!(Delegate::freeze(Origin::signed(1), 2));
+        assert_eq!(Delegate::frozen(2), Some(1));
+        assert_noop!(
+            Delegate::unfreeze(Origin::signed(2), 2),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(1), 2));
+        // a frozen subtree cannot move out from under the freezer
+        assert_ok!(Delegate::create_root(Origin::signed(2)));
+        assert_ok!(Delegate::freeze(Origin::signed(1), 2));
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(2), 2, 4),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_noop!(
+            Delegate::unfreeze(Origin::signed(2), 2),
+            Error::<TestRuntime>::NotAuthorized
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(1), 2));
+        // nor from under a frozen parent or into one
+        assert_ok!(Delegate::freeze(Origin::signed(1), 1));
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(2), 2, 4),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(1), 1));
+        assert_ok!(Delegate::freeze(Origin::signed(2), 4));
+        assert_noop!(
+            Delegate::move_subtree(Origin::signed(2), 2, 4),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_ok!(Delegate::unfreeze(Origin::signed(2), 4));
+        // a frozen root is revoked like any other tree
+        assert_ok!(Delegate::freeze(Origin::signed(1), 0));
+        assert_noop!(
+            Delegate::add_members(Origin::signed(1), 0, vec![6]),
+            Error::<TestRuntime>::TreeFrozen
+        );
+        assert_ok!(Delegate::revoke(Origin::signed(1), 0, false));
+        assert!(Delegate::frozen(0).is_none());
+    });
+}
+
+/// Accounts 2 to 9, so that duplicates are frequent
+fn accounts(seeds: Vec<u8>) -> Vec<u64> {
+    seeds.into_iter().map(|s| u64::from(s % 8) + 2).collect()
+}
+
+quickcheck! {
+    fn bounded_set_is_sorted_and_unique(items: Vec<u8>) -> bool {
+        let unique = items.iter().cloned().collect::<BTreeSet<_>>();
+        match BoundedSet::<u8, MaxSize>::try_from_vec(items) {
+            Some(set) => {
+                unique.len() <= 5
+                    && set.len() as usize == unique.len()
+                    && set.into_vec() == unique.into_iter().collect::<Vec<_>>()
+            }
+            None => unique.len() > 5,
+        }
+    }
+
+    fn delegate_invites_each_member_once(seeds: Vec<u8>) -> bool {
+        let members = accounts(seeds);
+        let unique = members.iter().cloned().collect::<BTreeSet<_>>();
+        new_test_ext().execute_with(|| {
+            assert_ok!(Delegate::create_root(Origin::signed(1)));
+            let result =
+                Delegate::delegate(Origin::signed(1), 0, members, None);
+            if unique.len() > 5 {
+                let error = Error::<TestRuntime>::TooManyInitialMembers;
+                result == Err(error.into()) && Delegate::trees(1).is_none()
+            } else {
+                // members join once they accept
+                let size = Delegate::trees(1).map(|t| t.size);
+                let invited = <PendingInvites<TestRuntime>>::iter_prefix(1)
+                    .map(|(a, _)| a)
+                    .collect::<BTreeSet<_>>();
+                result.is_ok() && size == Some(0) && invited == unique
+            }
+        })
+    }
+
+    fn add_members_counts_each_invite_once(seeds: Vec<u8>) -> bool {
+        let members = accounts(seeds);
+        let unique = members.iter().cloned().collect::<BTreeSet<_>>();
+        new_test_ext().execute_with(|| {
+            assert_ok!(Delegate::create_root(Origin::signed(1)));
+            let result = Delegate::add_members(Origin::signed(1), 0, members);
+            let invited = <PendingInvites<TestRuntime>>::iter_prefix(0)
+                .map(|(a, _)| a)
+                .collect::<BTreeSet<_>>();
+            if 1 + unique.len() > 5 {
+                let error = Error::<TestRuntime>::CannotAddGroupAboveMaxSize;
+                result == Err(error.into()) && invited.is_empty()
+            } else {
+                // the owner's bond for a root of 1 + n members, none
+                // without invitees
+                let n = unique.len() as u64;
+                let bond = if n == 0 { 0 } else { 2 * (1 + n) };
+                result.is_ok()
+                    && invited == unique
+                    && Delegate::inviter_bond(0, 1) == bond
+            }
+        })
+    }
+}